@@ -0,0 +1,45 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use fog::TextAnalyzer;
+use std::hint::black_box;
+
+const SAMPLE: &str = "The quick brown fox jumps over the lazy dog. \
+                      This is a simple sentence to demonstrate the algorithm. \
+                      Extraordinary complications arise from miscellaneous circumstances. \
+                      The complexity of this text should be relatively moderate. ";
+
+// A mix of short, long, silent-e, hyphenated and all-caps words
+const WORDS: &[&str] = &[
+    "the", "cat", "beautiful", "queue", "table", "create", "extraordinary", "miscellaneous", "rhythm",
+    "well-known", "HTML", "interested", "yellow", "area", "complications",
+];
+
+fn analyze_text(c: &mut Criterion) {
+    let analyzer = TextAnalyzer::new();
+    let mut group = c.benchmark_group("analyze_text");
+    for (size, repeat) in [("small", 1), ("medium", 100), ("large", 1000)] {
+        let text = SAMPLE.repeat(repeat);
+        // Per-word throughput makes the three sizes comparable
+        group.throughput(Throughput::Elements(analyzer.count(&text).word_count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &text, |b, text| {
+            b.iter(|| analyzer.analyze_text(black_box(text)))
+        });
+    }
+    group.finish();
+}
+
+fn count_syllables(c: &mut Criterion) {
+    let analyzer = TextAnalyzer::new();
+    let mut group = c.benchmark_group("count_syllables");
+    group.throughput(Throughput::Elements(WORDS.len() as u64));
+    group.bench_function("mixed words", |b| {
+        b.iter(|| {
+            for word in WORDS {
+                black_box(analyzer.count_syllables_explained(black_box(word)).count);
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, analyze_text, count_syllables);
+criterion_main!(benches);
@@ -0,0 +1,23 @@
+// Feeds arbitrary bytes, decoded lossily, to `analyze_text` and checks that
+// it neither panics nor produces a NaN or infinite score. From the fuzz
+// directory, with cargo-fuzz installed (`cargo install cargo-fuzz`):
+//
+//     cargo +nightly fuzz run analyze_text
+//
+// Crashing inputs are saved under fuzz/artifacts/analyze_text and can be
+// replayed by passing the file after the target name.
+#![no_main]
+
+use fog::TextAnalyzer;
+use libfuzzer_sys::fuzz_target;
+use std::sync::LazyLock;
+
+static ANALYZER: LazyLock<TextAnalyzer> = LazyLock::new(TextAnalyzer::new);
+
+fuzz_target!(|data: &[u8]| {
+    let text = String::from_utf8_lossy(data);
+    let metrics = ANALYZER.analyze_text(&text);
+    for (field, value) in metrics.as_map() {
+        assert!(value.is_finite(), "{} is {} for {:?}", field, value, text);
+    }
+});
@@ -0,0 +1,106 @@
+use crate::{AnalyzerError, TextAnalyzer, TextMetrics};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+// Metrics of files already analyzed, reused while a file's modification
+// time and size are unchanged. Give it to an analyzer with
+// `TextAnalyzer::with_cache`; an `Arc` of it can be shared by analyzers with
+// the same settings.
+#[derive(Debug, Default)]
+pub struct AnalysisCache {
+    entries: Mutex<HashMap<PathBuf, Entry>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    modified: SystemTime,
+    len: u64,
+    metrics: TextMetrics,
+}
+
+impl AnalysisCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    // The cached metrics for `path`, or those from `analyze` once it
+    // succeeds. Files whose modification time can't be read aren't cached.
+    pub(crate) fn get_or_analyze<F>(&self, path: &Path, analyze: F) -> Result<TextMetrics, AnalyzerError>
+    where
+        F: FnOnce() -> Result<TextMetrics, AnalyzerError>,
+    {
+        let metadata = fs::metadata(path)?;
+        let Ok(modified) = metadata.modified() else {
+            return analyze();
+        };
+        let len = metadata.len();
+        if let Some(entry) = self.entries.lock().unwrap().get(path) {
+            if entry.modified == modified && entry.len == len {
+                log::debug!("{}: unchanged, using cached metrics", path.display());
+                return Ok(entry.metrics.clone());
+            }
+        }
+        let metrics = analyze()?;
+        let entry = Entry { modified, len, metrics: metrics.clone() };
+        self.entries.lock().unwrap().insert(path.to_path_buf(), entry);
+        Ok(metrics)
+    }
+}
+
+impl TextAnalyzer {
+    // Makes `analyze_file` and the batch functions built on it reuse
+    // `cache` for files that haven't changed
+    pub fn with_cache(mut self, cache: Arc<AnalysisCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_invalidation() {
+        let path = std::env::temp_dir().join("fog_test_cache.txt");
+        fs::write(&path, "The cat sat on the mat.").unwrap();
+        let cache = Arc::new(AnalysisCache::new());
+        let analyzer = TextAnalyzer::new().with_cache(Arc::clone(&cache));
+        let name = path.to_str().unwrap();
+
+        let first = analyzer.analyze_file(name).unwrap();
+        assert_eq!(cache.len(), 1);
+        assert_eq!(analyzer.analyze_file(name).unwrap(), first);
+
+        // A cached entry is returned as is, so one seeded with other metrics
+        // shows whether the file was read again
+        let seeded = TextAnalyzer::new().analyze_text("Dogs run.");
+        cache.entries.lock().unwrap().get_mut(&path).unwrap().metrics = seeded.clone();
+        assert_eq!(analyzer.analyze_file(name).unwrap(), seeded);
+
+        fs::write(&path, "The cat sat on the mat. Then it slept.").unwrap();
+        let changed = analyzer.analyze_file(name).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(changed.sentence_count, 2);
+        assert_eq!(cache.len(), 1);
+        assert!(matches!(analyzer.analyze_file(name), Err(AnalyzerError::NotFound(_))));
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{CitationStripping, NumberHandling, SmogMode, TextAnalyzer, MAX_WORD_LENGTH};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Analyzer settings as read from a `.readability.toml` file, e.g.
+//
+//     complex_syllable_threshold = 4
+//     sentence_terminators = ".!?;"
+//     dale_chall_list = "lists/dale-chall.txt"
+//     markdown = true
+//     numbers = "spoken"
+//
+// Every field is optional and falls back to the `TextAnalyzer::new` default.
+// Word list paths are relative to the config file.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TextAnalyzerConfig {
+    pub vowels: Option<String>,
+    pub sentence_terminators: Option<String>,
+    pub complex_syllable_threshold: usize,
+    pub exclude_inflections: bool,
+    pub conditional_y: bool,
+    pub affix_rules: bool,
+    // Files with one word per line
+    pub dale_chall_list: Option<PathBuf>,
+    pub spache_list: Option<PathBuf>,
+    pub complexity_exclusions: Option<PathBuf>,
+    pub list_stemming: bool,
+    #[cfg(feature = "cmudict")]
+    pub cmudict: Option<PathBuf>,
+    pub markdown: bool,
+    pub html: bool,
+    pub exclude_code: bool,
+    pub citations: CitationStripping,
+    pub normalize_punctuation: bool,
+    pub rejoin_lines: bool,
+    pub cjk: bool,
+    pub lossy: bool,
+    pub max_word_length: usize,
+    pub numbers: NumberHandling,
+    pub smog_mode: SmogMode,
+    pub surface_forms: bool,
+}
+
+impl Default for TextAnalyzerConfig {
+    fn default() -> Self {
+        TextAnalyzerConfig {
+            vowels: None,
+            sentence_terminators: None,
+            complex_syllable_threshold: 3,
+            exclude_inflections: true,
+            conditional_y: false,
+            affix_rules: false,
+            dale_chall_list: None,
+            spache_list: None,
+            complexity_exclusions: None,
+            list_stemming: false,
+            #[cfg(feature = "cmudict")]
+            cmudict: None,
+            markdown: false,
+            html: false,
+            exclude_code: false,
+            citations: CitationStripping::default(),
+            normalize_punctuation: false,
+            rejoin_lines: false,
+            cjk: false,
+            lossy: false,
+            max_word_length: MAX_WORD_LENGTH,
+            numbers: NumberHandling::default(),
+            smog_mode: SmogMode::default(),
+            surface_forms: false,
+        }
+    }
+}
+
+impl TextAnalyzerConfig {
+    pub const FILE_NAME: &'static str = ".readability.toml";
+
+    // Invalid TOML and unknown fields are `InvalidData` errors
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut config: Self = toml::from_str(&fs::read_to_string(path)?)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.message().to_string()))?;
+        let base = path.parent().unwrap_or(Path::new(""));
+        for list in [&mut config.dale_chall_list, &mut config.spache_list, &mut config.complexity_exclusions].into_iter().flatten() {
+            *list = base.join(&*list);
+        }
+        #[cfg(feature = "cmudict")]
+        if let Some(cmudict) = &mut config.cmudict {
+            *cmudict = base.join(&*cmudict);
+        }
+        Ok(config)
+    }
+
+    // Loads `.readability.toml` from `dir` if there is one
+    pub fn discover<P: AsRef<Path>>(dir: P) -> io::Result<Option<Self>> {
+        let path = dir.as_ref().join(Self::FILE_NAME);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        Self::load(path).map(Some)
+    }
+}
+
+impl TextAnalyzer {
+    // Fails only if a word list or dictionary named in the config can't be read
+    pub fn from_config(config: &TextAnalyzerConfig) -> io::Result<Self> {
+        let mut analyzer = TextAnalyzer::builder()
+            .complex_syllable_threshold(config.complex_syllable_threshold)
+            .exclude_inflections(config.exclude_inflections)
+            .build()
+            .with_conditional_y(config.conditional_y)
+            .with_affix_rules(config.affix_rules)
+            .with_list_stemming(config.list_stemming)
+            .with_markdown_stripping(config.markdown)
+            .with_html_stripping(config.html)
+            .with_code_exclusion(config.exclude_code)
+            .with_citation_stripping(config.citations)
+            .with_punctuation_normalization(config.normalize_punctuation)
+            .with_line_rejoining(config.rejoin_lines)
+            .with_cjk_mode(config.cjk)
+            .with_lossy_decoding(config.lossy)
+            .with_max_word_length(config.max_word_length)
+            .with_number_handling(config.numbers)
+            .with_smog_mode(config.smog_mode)
+            .with_surface_forms(config.surface_forms);
+        if let Some(vowels) = &config.vowels {
+            analyzer = analyzer.with_vowels(vowels);
+        }
+        if let Some(terminators) = &config.sentence_terminators {
+            analyzer = analyzer.with_sentence_terminators(terminators);
+        }
+        if let Some(path) = &config.dale_chall_list {
+            analyzer = analyzer.with_dale_chall_list(load_word_list(path)?);
+        }
+        if let Some(path) = &config.spache_list {
+            analyzer = analyzer.with_spache_list(load_word_list(path)?);
+        }
+        if let Some(path) = &config.complexity_exclusions {
+            analyzer = analyzer.with_complexity_exclusions(load_word_list(path)?);
+        }
+        #[cfg(feature = "cmudict")]
+        if let Some(path) = &config.cmudict {
+            analyzer = analyzer.with_cmudict(path)?;
+        }
+        Ok(analyzer)
+    }
+}
+
+fn load_word_list(path: &Path) -> io::Result<HashSet<String>> {
+    let contents = fs::read_to_string(path)?;
+    Ok(contents.lines().map(str::trim).filter(|line| !line.is_empty()).map(String::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_config() {
+        let dir = std::env::temp_dir().join("fog_test_config");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("easy.txt"), "the\ncat\nsat\n").unwrap();
+        fs::write(
+            dir.join(TextAnalyzerConfig::FILE_NAME),
+            "sentence_terminators = \".!?;\"\ndale_chall_list = \"easy.txt\"\nnumbers = \"spoken\"\n",
+        )
+        .unwrap();
+        let config = TextAnalyzerConfig::discover(&dir).unwrap().unwrap();
+        fs::write(dir.join(TextAnalyzerConfig::FILE_NAME), "complex_syllables = 4\n").unwrap();
+        let typo = TextAnalyzerConfig::load(dir.join(TextAnalyzerConfig::FILE_NAME));
+        let analyzer = TextAnalyzer::from_config(&config).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(config.numbers, NumberHandling::Spoken);
+        // Omitted fields keep their defaults
+        assert_eq!(config.complex_syllable_threshold, 3);
+        assert!(config.exclude_inflections);
+        assert_eq!(typo.unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        let metrics = analyzer.analyze_text("The cat sat; the 2 dogs ran.");
+        assert_eq!(metrics.sentence_count, 2);
+        // "2" is a word, and "dogs" and "ran" are missing from the list
+        assert_eq!(metrics.word_count, 7);
+        assert_eq!(metrics.difficult_word_count, 2);
+        assert!(TextAnalyzerConfig::discover(&dir).unwrap().is_none());
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{strip_html, TextAnalyzer, TextMetrics};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::LazyLock;
+
+static ROOTFILE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"<rootfile\b[^>]*\bfull-path="([^"]+)""#).unwrap());
+static ITEM: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<item\b[^>]*>").unwrap());
+static ITEMREF: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"<itemref\b[^>]*\bidref="([^"]+)""#).unwrap());
+static ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"\b(id|href)="([^"]*)""#).unwrap());
+static HEAD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?is)<head\b.*?</head\s*>").unwrap());
+
+impl TextAnalyzer {
+    // Analyzes the chapters of an EPUB book as one text
+    pub fn analyze_epub<P: AsRef<Path>>(&self, path: P) -> io::Result<TextMetrics> {
+        Ok(self.analyze_text(&self.read_epub_text(path)?))
+    }
+
+    // The visible text of every chapter, separated by blank lines
+    pub fn read_epub_text<P: AsRef<Path>>(&self, path: P) -> io::Result<String> {
+        let chapters: Vec<String> = read_chapters(path.as_ref())?.into_iter().map(|(_, text)| text).collect();
+        Ok(chapters.join("\n\n"))
+    }
+
+    // Metrics for each chapter in reading order, named by its path in the book
+    pub fn analyze_epub_chapters<P: AsRef<Path>>(&self, path: P) -> io::Result<Vec<(String, TextMetrics)>> {
+        let chapters = read_chapters(path.as_ref())?;
+        Ok(chapters.into_iter().map(|(name, text)| (name, self.analyze_text(&text))).collect())
+    }
+}
+
+// The visible text of each document in the spine, in spine order. The
+// container and package files are read with patterns rather than an XML
+// parser, which is enough for the attributes needed here.
+fn read_chapters(path: &Path) -> io::Result<Vec<(String, String)>> {
+    let mut archive = zip::ZipArchive::new(File::open(path)?).map_err(invalid)?;
+    let container = read_entry(&mut archive, "META-INF/container.xml")?;
+    let package_path = ROOTFILE
+        .captures(&container)
+        .map(|caps| caps[1].to_string())
+        .ok_or_else(|| invalid("container.xml names no package file"))?;
+    let package = read_entry(&mut archive, &package_path)?;
+
+    // Chapter paths in the manifest are relative to the package file
+    let base = package_path.rfind('/').map_or("", |slash| &package_path[..=slash]);
+    let manifest: HashMap<String, String> = ITEM
+        .find_iter(&package)
+        .filter_map(|item| {
+            let attributes: HashMap<&str, &str> = ATTRIBUTE
+                .captures_iter(item.as_str())
+                .map(|caps| (caps.get(1).unwrap().as_str(), caps.get(2).unwrap().as_str()))
+                .collect();
+            Some((attributes.get("id")?.to_string(), format!("{}{}", base, attributes.get("href")?)))
+        })
+        .collect();
+
+    ITEMREF
+        .captures_iter(&package)
+        .map(|caps| {
+            let name = manifest
+                .get(&caps[1])
+                .ok_or_else(|| invalid(format!("spine item {} is not in the manifest", &caps[1])))?;
+            let document = read_entry(&mut archive, name)?;
+            Ok((name.clone(), strip_html(&HEAD.replace(&document, " "))))
+        })
+        .collect()
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<File>, name: &str) -> io::Result<String> {
+    let mut entry = archive.by_name(name).map_err(invalid)?;
+    let mut contents = String::new();
+    entry.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+fn invalid<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> String {
+        format!("{}/tests/fixtures/tiny.epub", env!("CARGO_MANIFEST_DIR"))
+    }
+
+    #[test]
+    fn test_analyze_epub() {
+        let analyzer = TextAnalyzer::new();
+        let chapters = analyzer.analyze_epub_chapters(fixture()).unwrap();
+        let names: Vec<&str> = chapters.iter().map(|(name, _)| name.as_str()).collect();
+        // Spine order, not manifest order, and the navigation page is left out
+        assert_eq!(names, ["OEBPS/text/chapter1.xhtml", "OEBPS/text/chapter2.xhtml"]);
+        // Chapter One The cat sat on the mat. It was happy.
+        assert_eq!(chapters[0].1.word_count, 11);
+        assert_eq!(chapters[0].1.sentence_count, 2);
+
+        let book = analyzer.analyze_epub(fixture()).unwrap();
+        let expected = analyzer.analyze_text(
+            "Chapter One The cat sat on the mat. It was happy.\n\n\
+             Chapter Two Extraordinary complications arose & persisted.",
+        );
+        assert_eq!(book, expected);
+        assert!(analyzer.analyze_epub(format!("{}/tests/fixtures/sample.txt", env!("CARGO_MANIFEST_DIR"))).is_err());
+    }
+}
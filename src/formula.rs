@@ -0,0 +1,241 @@
+use crate::RawCounts;
+
+// A score computed from the raw counts of a text. The built-in formulas below
+// fill the TextMetrics fields named by `name`; formulas passed to
+// `TextAnalyzer::with_formula` end up in `TextMetrics::custom`.
+pub trait ReadabilityFormula: Send + Sync {
+    fn name(&self) -> &str;
+    fn score(&self, counts: &RawCounts) -> f64;
+}
+
+pub struct GunningFog;
+pub struct FleschKincaidGrade;
+pub struct FleschReadingEase;
+pub struct Smog;
+pub struct ColemanLiau;
+pub struct AutomatedReadability;
+pub struct DaleChall;
+pub struct Spache;
+pub struct Lix;
+pub struct Rix;
+pub struct LinsearWrite;
+pub struct Forcast;
+pub struct Eflaw;
+
+impl ReadabilityFormula for GunningFog {
+    fn name(&self) -> &str {
+        "gunning_fog_index"
+    }
+
+    fn score(&self, counts: &RawCounts) -> f64 {
+        let (words, sentences) = (counts.word_count, counts.sentence_count);
+        if words == 0 || sentences == 0 {
+            return 0.0;
+        }
+        0.4 * ((words as f64 / sentences as f64) + 100.0 * (counts.complex_word_count as f64 / words as f64))
+    }
+}
+
+impl ReadabilityFormula for FleschKincaidGrade {
+    fn name(&self) -> &str {
+        "flesch_kincaid_grade"
+    }
+
+    fn score(&self, counts: &RawCounts) -> f64 {
+        let (words, sentences) = (counts.word_count, counts.sentence_count);
+        if words == 0 || sentences == 0 {
+            return 0.0;
+        }
+        0.39 * (words as f64 / sentences as f64) + 11.8 * (counts.syllable_count as f64 / words as f64) - 15.59
+    }
+}
+
+impl ReadabilityFormula for FleschReadingEase {
+    fn name(&self) -> &str {
+        "flesch_reading_ease"
+    }
+
+    fn score(&self, counts: &RawCounts) -> f64 {
+        let (words, sentences) = (counts.word_count, counts.sentence_count);
+        if words == 0 || sentences == 0 {
+            return 0.0;
+        }
+        206.835 - 1.015 * (words as f64 / sentences as f64) - 84.6 * (counts.syllable_count as f64 / words as f64)
+    }
+}
+
+// SMOG proper counts the polysyllables in a sample of 30 sentences; scaling
+// the whole text's count to 30 sentences approximates that, and matches it
+// when polysyllables are spread evenly. See `SmogMode` for the sampled form.
+// Not valid under 30 sentences; TextMetrics reports None there.
+impl ReadabilityFormula for Smog {
+    fn name(&self) -> &str {
+        "smog_index"
+    }
+
+    fn score(&self, counts: &RawCounts) -> f64 {
+        if counts.sentence_count == 0 {
+            return 0.0;
+        }
+        1.0430 * f64::sqrt(counts.polysyllable_count as f64 * (30.0 / counts.sentence_count as f64)) + 3.1291
+    }
+}
+
+impl ReadabilityFormula for ColemanLiau {
+    fn name(&self) -> &str {
+        "coleman_liau_index"
+    }
+
+    fn score(&self, counts: &RawCounts) -> f64 {
+        let (words, sentences) = (counts.word_count, counts.sentence_count);
+        if words == 0 || sentences == 0 {
+            return 0.0;
+        }
+        // L = letters per 100 words, S = sentences per 100 words
+        let letters_per_100 = counts.letter_count as f64 / words as f64 * 100.0;
+        let sentences_per_100 = sentences as f64 / words as f64 * 100.0;
+        0.0588 * letters_per_100 - 0.296 * sentences_per_100 - 15.8
+    }
+}
+
+// ARI counts letters and digits, excluding spaces and punctuation
+impl ReadabilityFormula for AutomatedReadability {
+    fn name(&self) -> &str {
+        "automated_readability_index"
+    }
+
+    fn score(&self, counts: &RawCounts) -> f64 {
+        let (words, sentences) = (counts.word_count, counts.sentence_count);
+        if words == 0 || sentences == 0 {
+            return 0.0;
+        }
+        let characters = counts.alphanumeric_count as f64;
+        4.71 * (characters / words as f64) + 0.5 * (words as f64 / sentences as f64) - 21.43
+    }
+}
+
+// Needs a word list; TextMetrics reports 0.0 when the analyzer has none
+impl ReadabilityFormula for DaleChall {
+    fn name(&self) -> &str {
+        "dale_chall_score"
+    }
+
+    fn score(&self, counts: &RawCounts) -> f64 {
+        let (words, sentences) = (counts.word_count, counts.sentence_count);
+        if words == 0 || sentences == 0 {
+            return 0.0;
+        }
+        let difficult_percent = counts.difficult_word_count as f64 / words as f64 * 100.0;
+        let mut score = 0.1579 * difficult_percent + 0.0496 * (words as f64 / sentences as f64);
+        if difficult_percent > 5.0 {
+            score += 3.6365;
+        }
+        score
+    }
+}
+
+// Needs a word list, like Dale-Chall
+impl ReadabilityFormula for Spache {
+    fn name(&self) -> &str {
+        "spache_score"
+    }
+
+    fn score(&self, counts: &RawCounts) -> f64 {
+        let (words, sentences) = (counts.word_count, counts.sentence_count);
+        if words == 0 || sentences == 0 {
+            return 0.0;
+        }
+        let unfamiliar_percent = counts.unfamiliar_word_count as f64 / words as f64 * 100.0;
+        0.141 * (words as f64 / sentences as f64) + 0.086 * unfamiliar_percent + 0.839
+    }
+}
+
+// Long words are those with more than 6 letters
+impl ReadabilityFormula for Lix {
+    fn name(&self) -> &str {
+        "lix_index"
+    }
+
+    fn score(&self, counts: &RawCounts) -> f64 {
+        let (words, sentences) = (counts.word_count, counts.sentence_count);
+        if words == 0 || sentences == 0 {
+            return 0.0;
+        }
+        words as f64 / sentences as f64 + (counts.long_word_count as f64 * 100.0 / words as f64)
+    }
+}
+
+// Anderson's simplification of LIX, with the same long words
+impl ReadabilityFormula for Rix {
+    fn name(&self) -> &str {
+        "rix_index"
+    }
+
+    fn score(&self, counts: &RawCounts) -> f64 {
+        rix(counts.long_word_count, counts.sentence_count)
+    }
+}
+
+// Long words per sentence, 0.0 without sentences
+pub(crate) fn rix(long_words: usize, sentences: usize) -> f64 {
+    if sentences == 0 {
+        return 0.0;
+    }
+    long_words as f64 / sentences as f64
+}
+
+// Easy words have at most 2 syllables, hard words 3 or more. The standard
+// formula uses a 100-word sample; scoring the whole text keeps the same
+// per-sentence ratio without having to pick a sample.
+impl ReadabilityFormula for LinsearWrite {
+    fn name(&self) -> &str {
+        "linsear_write"
+    }
+
+    fn score(&self, counts: &RawCounts) -> f64 {
+        if counts.word_count == 0 || counts.sentence_count == 0 {
+            return 0.0;
+        }
+        let hard_words = counts.polysyllable_count as f64;
+        let easy_words = counts.word_count as f64 - hard_words;
+        let provisional = (easy_words + hard_words * 3.0) / counts.sentence_count as f64;
+        if provisional > 20.0 {
+            provisional / 2.0
+        } else {
+            (provisional - 2.0) / 2.0
+        }
+    }
+}
+
+// FORCAST ignores sentences, so it also suits forms and lists. The standard
+// 150-word sample is replaced by scaling the whole text to 150 words, as
+// with Linsear Write.
+impl ReadabilityFormula for Forcast {
+    fn name(&self) -> &str {
+        "forcast_index"
+    }
+
+    fn score(&self, counts: &RawCounts) -> f64 {
+        if counts.word_count == 0 {
+            return 0.0;
+        }
+        let monosyllables_per_150 = counts.monosyllable_count as f64 * 150.0 / counts.word_count as f64;
+        20.0 - monosyllables_per_150 / 10.0
+    }
+}
+
+// McAlpine's EFLAW, for readers of English as a foreign language. Mini words
+// have at most 3 letters; lower scores are easier, with 20 or less very easy
+// and over 30 confusing.
+impl ReadabilityFormula for Eflaw {
+    fn name(&self) -> &str {
+        "eflaw_score"
+    }
+
+    fn score(&self, counts: &RawCounts) -> f64 {
+        if counts.sentence_count == 0 {
+            return 0.0;
+        }
+        (counts.word_count + counts.mini_word_count) as f64 / counts.sentence_count as f64
+    }
+}
@@ -0,0 +1,3869 @@
+use regex::Regex;
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::{self, File};
+use std::io;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::{BufRead, BufReader, Read};
+#[cfg(any(feature = "cmudict", not(target_arch = "wasm32")))]
+use std::path::Path;
+use std::sync::{Arc, LazyLock, Once};
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod cache;
+#[cfg(feature = "config")]
+mod config;
+#[cfg(feature = "epub")]
+mod epub;
+mod formula;
+mod partial;
+mod preprocess;
+mod template;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use formula::{
+    AutomatedReadability, ColemanLiau, DaleChall, Eflaw, FleschKincaidGrade, FleschReadingEase, Forcast,
+    GunningFog, LinsearWrite, Lix, ReadabilityFormula, Rix, Smog, Spache,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use cache::AnalysisCache;
+#[cfg(feature = "config")]
+pub use config::TextAnalyzerConfig;
+pub use partial::{MetricSet, PartialMetrics};
+pub use preprocess::{
+    normalize_punctuation, rejoin_hyphenated_lines, strip_citations, strip_code, strip_html, strip_markdown,
+};
+pub use template::TemplateError;
+
+#[cfg(not(target_arch = "wasm32"))]
+const CHUNK_SIZE: usize = 64 * 1024;
+
+#[cfg(not(target_arch = "wasm32"))]
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+#[cfg(not(target_arch = "wasm32"))]
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+#[cfg(not(target_arch = "wasm32"))]
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+#[cfg(all(feature = "gzip", not(target_arch = "wasm32")))]
+const GZIP_MAGIC: &[u8] = &[0x1F, 0x8B];
+
+// Number of entries kept in `WordStats::most_frequent`
+const TOP_WORDS: usize = 10;
+
+// Includes accented forms so loanwords like "café" and "résumé" count correctly
+const ENGLISH_VOWELS: &str = "aeiouyàáâäèéêëìíîïòóôöùúûüÿ";
+
+// A diaeresis marks a vowel that is pronounced separately ("naïve", "Zoë")
+const DIAERESIS_VOWELS: &str = "äëïöüÿ";
+
+// The longest words in general dictionaries have about 45 letters
+const MAX_WORD_LENGTH: usize = 64;
+const LETTERS_PER_SYLLABLE: usize = 3;
+
+// The Fry graph's axis ranges and its grade regions, each as the center of
+// the region along the graph's diagonal: (grade, syllables, sentences) per
+// 100 words
+const FRY_SYLLABLES: (f64, f64) = (108.0, 182.0);
+const FRY_SENTENCES: (f64, f64) = (3.6, 25.0);
+const FRY_GRADES: [(u32, f64, f64); 13] = [
+    (1, 112.0, 20.0),
+    (2, 118.0, 13.5),
+    (3, 124.0, 10.0),
+    (4, 130.0, 7.7),
+    (5, 136.0, 6.5),
+    (6, 141.0, 5.7),
+    (7, 146.0, 5.0),
+    (8, 151.0, 4.5),
+    (9, 156.0, 4.2),
+    (10, 161.0, 4.0),
+    (11, 166.0, 3.8),
+    (12, 171.0, 3.7),
+    (13, 177.0, 3.6),
+];
+
+// The same for the Raygor graph: (grade, long words, sentences) per 100
+// words, with 13 for college and 14 for professional
+const RAYGOR_LONG_WORDS: (f64, f64) = (6.0, 44.0);
+const RAYGOR_SENTENCES: (f64, f64) = (3.2, 25.0);
+const RAYGOR_GRADES: [(u32, f64, f64); 12] = [
+    (3, 12.0, 12.0),
+    (4, 15.0, 10.0),
+    (5, 18.0, 8.3),
+    (6, 20.0, 7.1),
+    (7, 22.0, 6.3),
+    (8, 24.0, 5.6),
+    (9, 26.0, 5.0),
+    (10, 28.0, 4.6),
+    (11, 30.0, 4.2),
+    (12, 32.0, 3.9),
+    (13, 35.0, 3.6),
+    (14, 39.0, 3.3),
+];
+
+const HYPHENS: &[char] = &['-', '‐'];
+
+const CLOSING_PUNCTUATION: &[char] = &['"', '\'', '”', '’', ')', ']'];
+
+// Abbreviations whose trailing period does not end a sentence. Ones that
+// commonly close a sentence ("etc.") are deliberately left out.
+const ABBREVIATIONS: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "vs", "mt", "gen", "col", "capt", "lt",
+    "e.g", "i.e", "u.s", "u.s.a", "u.k", "approx", "dept",
+];
+
+// Abbreviations only before a number, as in "fig. 3"; "no." otherwise
+// usually ends a sentence
+const NUMBERED_ABBREVIATIONS: &[&str] = &["no", "fig", "vol"];
+
+const BE_FORMS: &[&str] = &["am", "is", "are", "was", "were", "be", "been", "being"];
+
+// Common past participles that don't end in "-ed"
+const IRREGULAR_PARTICIPLES: &[&str] = &[
+    "begun", "bitten", "born", "bought", "broken", "brought", "built", "caught", "chosen", "done",
+    "drawn", "driven", "eaten", "fallen", "felt", "forgotten", "found", "frozen", "given", "gone",
+    "grown", "held", "hidden", "hit", "hurt", "kept", "known", "laid", "led", "left", "lost",
+    "made", "meant", "paid", "put", "read", "run", "said", "seen", "sent", "set", "shown", "shut",
+    "sold", "spent", "spoken", "stolen", "struck", "taken", "taught", "thought", "thrown", "told",
+    "understood", "won", "worn", "written",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextMetrics {
+    pub word_count: usize,
+    pub sentence_count: usize,
+    pub syllable_count: usize,
+    pub complex_word_count: usize,
+    pub difficult_word_count: usize,
+    pub unfamiliar_word_count: usize,
+    pub long_word_count: usize,
+    pub polysyllable_count: usize,
+    pub monosyllable_count: usize,
+    // Words of at most 3 letters, for EFLAW
+    pub mini_word_count: usize,
+    // Coleman-Liau uses letter_count and ARI uses alphanumeric_count;
+    // total_char_count also includes whitespace and punctuation
+    pub letter_count: usize,
+    pub alphanumeric_count: usize,
+    pub total_char_count: usize,
+    pub gunning_fog_index: f64,
+    pub flesch_kincaid_grade: f64,
+    pub flesch_reading_ease: f64,
+    // None for texts under 30 sentences, where SMOG is not valid
+    pub smog_index: Option<f64>,
+    pub coleman_liau_index: f64,
+    pub automated_readability_index: f64,
+    pub dale_chall_score: f64,
+    pub spache_score: f64,
+    pub lix_index: f64,
+    pub rix_index: f64,
+    pub linsear_write: f64,
+    pub forcast_index: f64,
+    pub eflaw_score: f64,
+    pub average_words_per_sentence: f64,
+    pub average_syllables_per_word: f64,
+    // Scores from formulas added with `TextAnalyzer::with_formula`, by name.
+    // Not part of the CSV columns.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "HashMap::is_empty"))]
+    pub custom: HashMap<String, f64>,
+}
+
+impl TextMetrics {
+    // Field names in declaration order, matching `csv_values`
+    pub const CSV_COLUMNS: &'static [&'static str] = &[
+        "word_count",
+        "sentence_count",
+        "syllable_count",
+        "complex_word_count",
+        "difficult_word_count",
+        "unfamiliar_word_count",
+        "long_word_count",
+        "polysyllable_count",
+        "monosyllable_count",
+        "mini_word_count",
+        "letter_count",
+        "alphanumeric_count",
+        "total_char_count",
+        "gunning_fog_index",
+        "flesch_kincaid_grade",
+        "flesch_reading_ease",
+        "smog_index",
+        "coleman_liau_index",
+        "automated_readability_index",
+        "dale_chall_score",
+        "spache_score",
+        "lix_index",
+        "rix_index",
+        "linsear_write",
+        "forcast_index",
+        "eflaw_score",
+        "average_words_per_sentence",
+        "average_syllables_per_word",
+    ];
+
+    // Counts as integers, scores with 4 decimal places; SMOG is left empty
+    // when it is not valid
+    pub fn csv_values(&self) -> Vec<String> {
+        self.counts()
+            .iter()
+            .map(|count| count.to_string())
+            .chain(self.scores().iter().map(|score| score.map_or_else(String::new, |s| format!("{:.4}", s))))
+            .collect()
+    }
+
+    // One `field: value` line per CSV column, then the custom scores by name.
+    // Counts are integers and scores are rounded to `precision` decimal
+    // places; SMOG reads N/A when it is not valid.
+    pub fn format_metrics(&self, precision: usize) -> String {
+        let counts = self.counts().map(|count| count.to_string());
+        let scores = self
+            .scores()
+            .map(|score| score.map_or_else(|| "N/A".to_string(), |s| format!("{:.*}", precision, s)));
+        let mut custom: Vec<_> = self.custom.iter().collect();
+        custom.sort_by(|a, b| a.0.cmp(b.0));
+        let mut report = String::new();
+        for (column, value) in Self::CSV_COLUMNS.iter().zip(counts.iter().chain(scores.iter())) {
+            report.push_str(&format!("{}: {}\n", column, value));
+        }
+        for (name, score) in custom {
+            report.push_str(&format!("{}: {:.*}\n", name, precision, score));
+        }
+        report
+    }
+
+    // Every CSV column by name, counts converted to f64. SMOG is left out
+    // when it is not valid, and custom scores are not included.
+    pub fn as_map(&self) -> BTreeMap<&'static str, f64> {
+        Self::CSV_COLUMNS
+            .iter()
+            .zip(self.column_values())
+            .filter_map(|(&column, value)| Some((column, value?)))
+            .collect()
+    }
+
+    fn raw_counts(&self) -> RawCounts {
+        RawCounts {
+            word_count: self.word_count,
+            sentence_count: self.sentence_count,
+            syllable_count: self.syllable_count,
+            complex_word_count: self.complex_word_count,
+            difficult_word_count: self.difficult_word_count,
+            unfamiliar_word_count: self.unfamiliar_word_count,
+            long_word_count: self.long_word_count,
+            polysyllable_count: self.polysyllable_count,
+            monosyllable_count: self.monosyllable_count,
+            mini_word_count: self.mini_word_count,
+            letter_count: self.letter_count,
+            alphanumeric_count: self.alphanumeric_count,
+            total_char_count: self.total_char_count,
+        }
+    }
+
+    // Every field in CSV_COLUMNS order, counts converted to f64
+    fn column_values(&self) -> Vec<Option<f64>> {
+        self.counts()
+            .iter()
+            .map(|&count| Some(count as f64))
+            .chain(self.scores())
+            .collect()
+    }
+
+    fn counts(&self) -> [usize; 13] {
+        [
+            self.word_count,
+            self.sentence_count,
+            self.syllable_count,
+            self.complex_word_count,
+            self.difficult_word_count,
+            self.unfamiliar_word_count,
+            self.long_word_count,
+            self.polysyllable_count,
+            self.monosyllable_count,
+            self.mini_word_count,
+            self.letter_count,
+            self.alphanumeric_count,
+            self.total_char_count,
+        ]
+    }
+
+    fn scores(&self) -> [Option<f64>; 15] {
+        [
+            Some(self.gunning_fog_index),
+            Some(self.flesch_kincaid_grade),
+            Some(self.flesch_reading_ease),
+            self.smog_index,
+            Some(self.coleman_liau_index),
+            Some(self.automated_readability_index),
+            Some(self.dale_chall_score),
+            Some(self.spache_score),
+            Some(self.lix_index),
+            Some(self.rix_index),
+            Some(self.linsear_write),
+            Some(self.forcast_index),
+            Some(self.eflaw_score),
+            Some(self.average_words_per_sentence),
+            Some(self.average_syllables_per_word),
+        ]
+    }
+
+    // Flesch's reading-ease bands
+    pub fn reading_level_label(&self) -> &'static str {
+        match self.flesch_reading_ease {
+            score if score >= 90.0 => "Very Easy (5th grade)",
+            score if score >= 80.0 => "Easy (6th grade)",
+            score if score >= 70.0 => "Fairly Easy (7th grade)",
+            score if score >= 60.0 => "Standard (8th–9th grade)",
+            score if score >= 50.0 => "Fairly Difficult (10th–12th grade)",
+            score if score >= 30.0 => "Difficult (college)",
+            _ => "Very Difficult (college graduate)",
+        }
+    }
+
+    // Average adult silent reading speed for non-fiction (Brysbaert, 2019)
+    pub const SILENT_READING_WPM: usize = 238;
+
+    // Panics if `words_per_minute` is 0
+    pub fn reading_time(&self, words_per_minute: usize) -> Duration {
+        assert!(words_per_minute > 0, "reading speed must be at least one word per minute");
+        Duration::from_secs_f64(self.word_count as f64 * 60.0 / words_per_minute as f64)
+    }
+
+    // Reading time at SILENT_READING_WPM to the nearest second, as "45 s" or
+    // "3 min 5 s"
+    pub fn reading_time_label(&self) -> String {
+        let seconds = self.reading_time(Self::SILENT_READING_WPM).as_secs_f64().round() as u64;
+        match (seconds / 60, seconds % 60) {
+            (0, seconds) => format!("{} s", seconds),
+            (minutes, seconds) => format!("{} min {} s", minutes, seconds),
+        }
+    }
+
+    // The grade band for `dale_chall_score` from the New Dale-Chall table.
+    // Only meaningful when the analyzer has a Dale-Chall list.
+    pub fn dale_chall_grade_range(&self) -> &'static str {
+        match self.dale_chall_score {
+            score if score >= 10.0 => "grades 16 and above (college graduate)",
+            score if score >= 9.0 => "grades 13–15 (college)",
+            score if score >= 8.0 => "grades 11–12",
+            score if score >= 7.0 => "grades 9–10",
+            score if score >= 6.0 => "grades 7–8",
+            score if score >= 5.0 => "grades 5–6",
+            _ => "grade 4 and below",
+        }
+    }
+
+    // How far the scores can be trusted given the amount of text: under 100
+    // words or 3 sentences is Low, under 300 words or 10 sentences Medium
+    pub fn reliability(&self) -> Reliability {
+        if self.word_count < 100 || self.sentence_count < 3 {
+            Reliability::Low
+        } else if self.word_count < 300 || self.sentence_count < 10 {
+            Reliability::Medium
+        } else {
+            Reliability::High
+        }
+    }
+
+    // Anderson's (1983) conversion table from RIX to school grade
+    pub fn rix_grade_label(&self) -> &'static str {
+        const GRADES: [(f64, &str); 12] = [
+            (7.2, "college"),
+            (6.2, "grade 12"),
+            (5.3, "grade 11"),
+            (4.5, "grade 10"),
+            (3.7, "grade 9"),
+            (3.0, "grade 8"),
+            (2.4, "grade 7"),
+            (1.8, "grade 6"),
+            (1.3, "grade 5"),
+            (0.8, "grade 4"),
+            (0.5, "grade 3"),
+            (0.2, "grade 2"),
+        ];
+        GRADES.iter().find(|(min, _)| self.rix_index >= *min).map_or("grade 1", |(_, grade)| grade)
+    }
+
+    // The mean of the Flesch-Kincaid, Gunning Fog, Coleman-Liau and ARI
+    // grades, and SMOG when it is valid, rounded to a whole grade. Very easy
+    // texts can average below zero; they are grade 0.
+    pub fn consensus_grade(&self) -> f64 {
+        let grades: Vec<f64> = [
+            Some(self.flesch_kincaid_grade),
+            Some(self.gunning_fog_index),
+            Some(self.coleman_liau_index),
+            Some(self.automated_readability_index),
+            self.smog_index,
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|grade| grade.is_finite())
+        .collect();
+        if grades.is_empty() {
+            return 0.0;
+        }
+        (grades.iter().sum::<f64>() / grades.len() as f64).round().max(0.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Reliability {
+    Low,
+    Medium,
+    High,
+}
+
+// The plain-text report printed by the CLI. Scores use the formatter's
+// precision, 1 decimal place by default: `format!("{:.2}", metrics)`.
+impl fmt::Display for TextMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(1);
+        writeln!(f, "Text Analysis Results:")?;
+        writeln!(f, "----------------------")?;
+        writeln!(f, "Word Count: {}", self.word_count)?;
+        writeln!(f, "Sentence Count: {}", self.sentence_count)?;
+        writeln!(f, "Complex Word Count: {}", self.complex_word_count)?;
+        writeln!(f, "Average Words per Sentence: {:.*}", precision, self.average_words_per_sentence)?;
+        writeln!(f, "Average Syllables per Word: {:.*}", precision, self.average_syllables_per_word)?;
+        writeln!(f, "Reading Time: {}", self.reading_time_label())?;
+        writeln!(f, "\nReadability Scores:")?;
+        writeln!(f, "------------------")?;
+        let scores = [
+            ("Gunning Fog Index", Some(self.gunning_fog_index)),
+            ("Flesch-Kincaid Grade Level", Some(self.flesch_kincaid_grade)),
+            ("Flesch Reading Ease", Some(self.flesch_reading_ease)),
+            ("SMOG Index", self.smog_index),
+            ("Coleman-Liau Index", Some(self.coleman_liau_index)),
+            ("Automated Readability Index", Some(self.automated_readability_index)),
+            ("Dale-Chall Score", Some(self.dale_chall_score)),
+            ("LIX", Some(self.lix_index)),
+            ("Linsear Write", Some(self.linsear_write)),
+            ("Spache Score", Some(self.spache_score)),
+            ("FORCAST", Some(self.forcast_index)),
+        ];
+        for (label, score) in scores {
+            match score {
+                Some(score) => writeln!(f, "{}: {:.*}", label, precision, score)?,
+                None => writeln!(f, "{}: N/A (needs 30+ sentences)", label)?,
+            }
+        }
+        writeln!(f, "RIX: {:.*} ({})", precision, self.rix_index, self.rix_grade_label())?;
+        writeln!(f, "McAlpine EFLAW: {:.*}", precision, self.eflaw_score)?;
+        write!(f, "\nReading Level: {}", self.reading_level_label())
+    }
+}
+
+// Renders a header row and one row per document, with the document path
+// (or any other label) in the first column
+pub fn to_csv(rows: &[(&str, &TextMetrics)]) -> String {
+    let mut csv = String::from("path");
+    for column in TextMetrics::CSV_COLUMNS {
+        csv.push(',');
+        csv.push_str(column);
+    }
+    csv.push('\n');
+
+    for (path, metrics) in rows {
+        csv.push_str(&csv_escape(path));
+        for value in metrics.csv_values() {
+            csv.push(',');
+            csv.push_str(&value);
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+pub fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TextMetrics {
+    // serde_json writes f64 values with full round-trip precision
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("TextMetrics is always serializable")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SentenceMetrics {
+    pub text: String,
+    // Where the sentence starts: a byte offset, and a line and column (in
+    // characters) counted from 1. These are positions in the text as
+    // analyzed, which differs from the input when a clean-up option such as
+    // `with_markdown_stripping` changes it.
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+    pub word_count: usize,
+    pub syllable_count: usize,
+    pub flesch_reading_ease: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WordStats {
+    // Words with their counts, most frequent first. Words are grouped
+    // case-insensitively and shown lowercased, or in their most common
+    // casing with `TextAnalyzer::with_surface_forms`.
+    pub most_frequent: Vec<(String, usize)>,
+    pub longest_word: Option<String>,
+    pub unique_words: usize,
+    pub type_token_ratio: f64,
+}
+
+// Words per sentence; `std_dev` is the population standard deviation
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SentenceLengthStats {
+    pub mean: f64,
+    pub min: usize,
+    pub max: usize,
+    pub std_dev: f64,
+}
+
+// Result of `TextAnalyzer::compare`: every TextMetrics field before and after
+// an edit, in CSV_COLUMNS order
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MetricsDiff {
+    pub fields: Vec<FieldDiff>,
+    pub direction: Direction,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldDiff {
+    pub field: String,
+    pub before: Option<f64>,
+    pub after: Option<f64>,
+    // None when either side has no value, as with SMOG on short texts
+    pub delta: Option<f64>,
+}
+
+// Result of `TextAnalyzer::aggregate`. `combined` scores the corpus as one
+// text, from counts summed over every document; `fields` gives the spread of
+// each TextMetrics field across documents, in CSV_COLUMNS order.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CorpusSummary {
+    pub documents: usize,
+    pub combined: TextMetrics,
+    pub fields: Vec<FieldSpread>,
+}
+
+// Population standard deviation. Documents without a value, as with SMOG on
+// short texts, are left out; both are None if no document has one.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldSpread {
+    pub field: String,
+    pub mean: Option<f64>,
+    pub std_dev: Option<f64>,
+}
+
+impl CorpusSummary {
+    pub fn get(&self, field: &str) -> Option<&FieldSpread> {
+        self.fields.iter().find(|spread| spread.field == field)
+    }
+}
+
+// Judged by the Flesch-Kincaid grade level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    Easier,
+    Harder,
+    Unchanged,
+}
+
+impl MetricsDiff {
+    pub fn new(before: &TextMetrics, after: &TextMetrics) -> Self {
+        let fields = TextMetrics::CSV_COLUMNS
+            .iter()
+            .zip(before.column_values())
+            .zip(after.column_values())
+            .map(|((field, before), after)| FieldDiff {
+                field: field.to_string(),
+                before,
+                after,
+                delta: before.zip(after).map(|(before, after)| after - before),
+            })
+            .collect();
+        let direction = match after.flesch_kincaid_grade - before.flesch_kincaid_grade {
+            change if change < 0.0 => Direction::Easier,
+            change if change > 0.0 => Direction::Harder,
+            _ => Direction::Unchanged,
+        };
+        MetricsDiff { fields, direction }
+    }
+
+    pub fn get(&self, field: &str) -> Option<&FieldDiff> {
+        self.fields.iter().find(|diff| diff.field == field)
+    }
+}
+
+impl fmt::Display for MetricsDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |value: Option<f64>| value.map_or_else(|| "N/A".to_string(), |v| format!("{:.1}", v));
+        for diff in &self.fields {
+            let delta = diff.delta.map_or_else(String::new, |d| format!(" ({:+.1})", d));
+            writeln!(f, "{}: {} -> {}{}", diff.field, show(diff.before), show(diff.after), delta)?;
+        }
+        let direction = match self.direction {
+            Direction::Easier => "easier",
+            Direction::Harder => "harder",
+            Direction::Unchanged => "unchanged",
+        };
+        write!(f, "Overall: {}", direction)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalysisError {
+    EmptyInput,
+    NoSentences,
+}
+
+impl fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalysisError::EmptyInput => write!(f, "input contains no words"),
+            AnalysisError::NoSentences => write!(f, "input contains no sentence terminators"),
+        }
+    }
+}
+
+impl std::error::Error for AnalysisError {}
+
+// Why a file cannot be decoded when lossy decoding is off; see
+// `AnalyzerError::Encoding`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodingError {
+    InvalidUtf8 { offset: usize },
+    InvalidUtf16,
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodingError::InvalidUtf8 { offset } => write!(f, "invalid UTF-8 at byte {}", offset),
+            EncodingError::InvalidUtf16 => write!(f, "invalid UTF-16"),
+        }
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+impl From<EncodingError> for io::Error {
+    fn from(error: EncodingError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+// Errors from analyzing files and readers
+#[derive(Debug)]
+pub enum AnalyzerError {
+    NotFound(std::path::PathBuf),
+    Io(io::Error),
+    Encoding(EncodingError),
+    // The input decoded to text without a single word
+    EmptyInput,
+}
+
+impl fmt::Display for AnalyzerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalyzerError::NotFound(_) => write!(f, "file not found"),
+            AnalyzerError::Io(error) => write!(f, "{}", error),
+            AnalyzerError::Encoding(error) => write!(f, "{}", error),
+            AnalyzerError::EmptyInput => write!(f, "input contains no words"),
+        }
+    }
+}
+
+impl std::error::Error for AnalyzerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AnalyzerError::Io(error) => Some(error),
+            AnalyzerError::Encoding(error) => Some(error),
+            AnalyzerError::NotFound(_) | AnalyzerError::EmptyInput => None,
+        }
+    }
+}
+
+// An `InvalidData` error carrying an EncodingError becomes `Encoding`
+impl From<io::Error> for AnalyzerError {
+    fn from(error: io::Error) -> Self {
+        match error.get_ref().and_then(|inner| inner.downcast_ref::<EncodingError>()) {
+            Some(&encoding) => AnalyzerError::Encoding(encoding),
+            None => AnalyzerError::Io(error),
+        }
+    }
+}
+
+impl From<EncodingError> for AnalyzerError {
+    fn from(error: EncodingError) -> Self {
+        AnalyzerError::Encoding(error)
+    }
+}
+
+// How numerals such as "2024", "1,000" and "3.14" are counted. Either way
+// their periods and commas never end a sentence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum NumberHandling {
+    // Not words at all, so they add no words or syllables
+    #[default]
+    Exclude,
+    // Words with the syllables of their English reading, "3.14" being
+    // "three point one four"; never complex, long or off a word list
+    Spoken,
+}
+
+// Which citations `TextAnalyzer::with_citation_stripping` removes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum CitationStripping {
+    #[default]
+    Off,
+    // Bracketed numbers like "[12]", Markdown footnotes and superscripts
+    Markers,
+    // Markers and parenthetical author-year citations like "(Smith, 2020)"
+    All,
+}
+
+// How SMOG is computed on texts of 30 or more sentences
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum SmogMode {
+    // Polysyllables in the whole text, scaled to 30 sentences
+    #[default]
+    Approximate,
+    // Polysyllables in the first, middle and last 10 sentences, as McLaughlin
+    // specified. Needs the whole text in memory.
+    Sampled,
+}
+
+// How `TextAnalyzer::count_syllables_explained` arrived at a count. For
+// `SyllableSource::Rules` the count is `vowel_groups`, less 1 for a silent
+// "e", raised to 1 if that leaves 0.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SyllableBreakdown {
+    // Runs of vowels after the hiatus and consonant-"y" rules
+    pub vowel_groups: usize,
+    pub silent_e: bool,
+    // Whether the adjustments left 0 and the count was raised to 1
+    pub clamped: bool,
+    pub count: usize,
+    pub source: SyllableSource,
+    // One breakdown per part of a hyphenated compound
+    pub parts: Vec<SyllableBreakdown>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyllableSource {
+    #[default]
+    Rules,
+    // Looked up with `TextAnalyzer::with_cmudict`
+    Dictionary,
+    // The sum of the parts of a hyphenated compound, or of a stem and the
+    // suffix split from it by `with_affix_rules`
+    Compound,
+    // Estimated from the length of a token past `with_max_word_length`
+    LengthEstimate,
+    // An initialism such as "HTML", read letter by letter
+    Spelled,
+}
+
+// Totals gathered from a text, the input to every readability formula
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RawCounts {
+    pub word_count: usize,
+    pub sentence_count: usize,
+    pub syllable_count: usize,
+    pub complex_word_count: usize,
+    pub difficult_word_count: usize,
+    pub unfamiliar_word_count: usize,
+    pub long_word_count: usize,
+    pub polysyllable_count: usize,
+    pub monosyllable_count: usize,
+    pub mini_word_count: usize,
+    pub letter_count: usize,
+    pub alphanumeric_count: usize,
+    pub total_char_count: usize,
+}
+
+#[derive(Clone)]
+pub struct TextAnalyzer {
+    patterns: Arc<Patterns>,
+    vowels: String,
+    dale_chall_list: Option<HashSet<String>>,
+    spache_list: Option<HashSet<String>>,
+    complex_syllable_threshold: usize,
+    exclude_inflections: bool,
+    strip_markdown: bool,
+    strip_html: bool,
+    exclude_code: bool,
+    lossy_decoding: bool,
+    #[cfg(feature = "cmudict")]
+    pronunciations: Option<HashMap<String, usize>>,
+    formulas: Vec<Arc<dyn ReadabilityFormula>>,
+    smog_mode: SmogMode,
+    max_word_length: usize,
+    numbers: NumberHandling,
+    conditional_y: bool,
+    list_stemming: bool,
+    surface_forms: bool,
+    normalize_punctuation: bool,
+    complexity_exclusions: HashSet<String>,
+    affix_rules: bool,
+    rejoin_lines: bool,
+    citations: CitationStripping,
+    cjk: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    cache: Option<Arc<AnalysisCache>>,
+}
+
+// Configures how complex words are defined for Gunning Fog. SMOG and
+// Linsear Write keep the canonical polysyllables of 3 or more syllables.
+pub struct TextAnalyzerBuilder {
+    complex_syllable_threshold: usize,
+    exclude_inflections: bool,
+}
+
+impl TextAnalyzerBuilder {
+    // Minimum syllables for a word to count as complex (default 3)
+    pub fn complex_syllable_threshold(mut self, syllables: usize) -> Self {
+        self.complex_syllable_threshold = syllables;
+        self
+    }
+
+    // Whether words ending in "ed", "es" or "ing" are never complex (default true)
+    pub fn exclude_inflections(mut self, exclude: bool) -> Self {
+        self.exclude_inflections = exclude;
+        self
+    }
+
+    pub fn build(self) -> TextAnalyzer {
+        let mut analyzer = TextAnalyzer::new();
+        analyzer.complex_syllable_threshold = self.complex_syllable_threshold;
+        analyzer.exclude_inflections = self.exclude_inflections;
+        analyzer
+    }
+}
+
+// The compiled patterns, shared by every analyzer and clone that uses the
+// same ones; `with_vowels` and `with_sentence_terminators` give an analyzer
+// its own copy
+#[derive(Debug, Clone)]
+struct Patterns {
+    // Contractions like "don't" and "we’ll" and hyphenated compounds like
+    // "well-known" stay a single word; dashes between spaces do not
+    word: Regex,
+    number: Regex,
+    sentence: Regex,
+    vowel: Regex,
+    // URLs and email addresses, whose periods and question marks don't end
+    // a sentence. Trailing punctuation is left outside the match.
+    address: Regex,
+    // Full stops and marks that end sentences in CJK mode, along with the
+    // usual terminators
+    cjk_sentence: Regex,
+}
+
+static DEFAULT_PATTERNS: LazyLock<Arc<Patterns>> = LazyLock::new(|| {
+    Arc::new(Patterns {
+        word: Regex::new(r"\b\p{L}+(?:['’\-‐]\p{L}+)*\b").unwrap(),
+        number: Regex::new(r"\b\d+(?:,\d{3})*(?:\.\d+)?\b").unwrap(),
+        sentence: Regex::new(r"[.!?]+").unwrap(),
+        vowel: Regex::new(&format!("[{}]+", ENGLISH_VOWELS)).unwrap(),
+        address: Regex::new(
+            r#"(?i)\b(?:https?://|www\.)[^\s<>"]*[^\s<>"'.,;:!?)\]]|[\w.+-]+@[\w-]+(?:\.[\w-]+)+"#,
+        )
+        .unwrap(),
+        cjk_sentence: Regex::new("[。！？｡]+").unwrap(),
+    })
+});
+
+// Shown the first time a text looks Chinese or Japanese but CJK mode is off
+static CJK_WARNING: Once = Once::new();
+
+impl Default for TextAnalyzer {
+    fn default() -> Self {
+        TextAnalyzer {
+            patterns: Arc::clone(&DEFAULT_PATTERNS),
+            vowels: ENGLISH_VOWELS.to_string(),
+            dale_chall_list: None,
+            spache_list: None,
+            complex_syllable_threshold: 3,
+            exclude_inflections: true,
+            strip_markdown: false,
+            strip_html: false,
+            exclude_code: false,
+            lossy_decoding: false,
+            #[cfg(feature = "cmudict")]
+            pronunciations: None,
+            formulas: Vec::new(),
+            smog_mode: SmogMode::default(),
+            max_word_length: MAX_WORD_LENGTH,
+            numbers: NumberHandling::default(),
+            conditional_y: false,
+            list_stemming: false,
+            surface_forms: false,
+            normalize_punctuation: false,
+            complexity_exclusions: HashSet::new(),
+            affix_rules: false,
+            rejoin_lines: false,
+            cjk: false,
+            citations: CitationStripping::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            cache: None,
+        }
+    }
+}
+
+impl TextAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn builder() -> TextAnalyzerBuilder {
+        TextAnalyzerBuilder {
+            complex_syllable_threshold: 3,
+            exclude_inflections: true,
+        }
+    }
+
+    // Replaces the English vowel set, e.g. "aeiouáéíóúü" for Spanish
+    pub fn with_vowels(mut self, vowels: &str) -> Self {
+        self.vowels = vowels.to_lowercase();
+        Arc::make_mut(&mut self.patterns).vowel = Regex::new(&format!("[{}]+", regex::escape(&self.vowels))).unwrap();
+        self
+    }
+
+    // Replaces the ".!?" terminators, e.g. ".!?;" to also break sentences at
+    // semicolons or "。！？" for Chinese and Japanese; runs of them count once
+    pub fn with_sentence_terminators(mut self, terminators: &str) -> Self {
+        Arc::make_mut(&mut self.patterns).sentence = Regex::new(&format!("[{}]+", regex::escape(terminators))).unwrap();
+        self
+    }
+
+    // Whether "y" counts as a vowel only when it is neither word-initial nor
+    // next to another vowel (default false, where "y" is always a vowel if
+    // it is in the vowel set)
+    pub fn with_conditional_y(mut self, enabled: bool) -> Self {
+        self.conditional_y = enabled;
+        self
+    }
+
+    // Whether suffixes like "-ful", "-ness" and "-ly" are counted apart from
+    // their stem, so the stem keeps its silent "e" ("careful", "lovely"), and
+    // a vowel before "-ation" is its own syllable ("creation") (default
+    // false). Miscounts some words that only look affixed, e.g. "element".
+    pub fn with_affix_rules(mut self, enabled: bool) -> Self {
+        self.affix_rules = enabled;
+        self
+    }
+
+    pub fn with_dale_chall_list(mut self, words: HashSet<String>) -> Self {
+        self.dale_chall_list = Some(words.into_iter().map(|w| w.to_lowercase()).collect());
+        self
+    }
+
+    pub fn with_spache_list(mut self, words: HashSet<String>) -> Self {
+        self.spache_list = Some(words.into_iter().map(|w| w.to_lowercase()).collect());
+        self
+    }
+
+    // Words that never count as complex for Gunning Fog, whatever their
+    // syllables, such as brand names or a field's everyday jargon. Matched
+    // case-insensitively; SMOG's polysyllable count is not affected.
+    pub fn with_complexity_exclusions(mut self, words: HashSet<String>) -> Self {
+        self.complexity_exclusions = words.into_iter().map(|w| w.to_lowercase()).collect();
+        self
+    }
+
+    // Whether a word missing from the Dale-Chall or Spache list still counts
+    // as listed when stripping an inflection like "-ed" or "-ing" gives a
+    // listed word (default false). Catches "jumped" for "jump", but can also
+    // match unrelated words, e.g. "hopes" for "hop".
+    pub fn with_list_stemming(mut self, enabled: bool) -> Self {
+        self.list_stemming = enabled;
+        self
+    }
+
+    // Analyze Markdown sources as prose; see `strip_markdown`
+    pub fn with_markdown_stripping(mut self, enabled: bool) -> Self {
+        self.strip_markdown = enabled;
+        self
+    }
+
+    // Analyze HTML pages by their visible text; see `strip_html`
+    pub fn with_html_stripping(mut self, enabled: bool) -> Self {
+        self.strip_html = enabled;
+        self
+    }
+
+    // Count syllables from a CMU Pronouncing Dictionary file where it lists
+    // the word, and with the heuristic otherwise
+    #[cfg(feature = "cmudict")]
+    pub fn with_cmudict<P: AsRef<Path>>(mut self, path: P) -> io::Result<Self> {
+        self.pronunciations = Some(load_cmudict(path.as_ref())?);
+        Ok(self)
+    }
+
+    // Adds a score to `TextMetrics::custom` under the formula's name
+    pub fn with_formula<F: ReadabilityFormula + 'static>(mut self, formula: F) -> Self {
+        self.formulas.push(Arc::new(formula));
+        self
+    }
+
+    // Whether `word_statistics` shows each frequent word in the casing it
+    // appears in most, instead of lowercased (default false). Ties go to the
+    // lowercase form if it occurs, and otherwise to the first one seen.
+    pub fn with_surface_forms(mut self, enabled: bool) -> Self {
+        self.surface_forms = enabled;
+        self
+    }
+
+    // Longer tokens get an estimated syllable count instead of a scan
+    pub fn with_max_word_length(mut self, length: usize) -> Self {
+        self.max_word_length = length;
+        self
+    }
+
+    pub fn with_number_handling(mut self, numbers: NumberHandling) -> Self {
+        self.numbers = numbers;
+        self
+    }
+
+    pub fn with_smog_mode(mut self, mode: SmogMode) -> Self {
+        self.smog_mode = mode;
+        self
+    }
+
+    // Map curly quotes, dashes and the ellipsis character to ASCII before
+    // counting, so "…" ends a sentence like "..."; see `normalize_punctuation`
+    pub fn with_punctuation_normalization(mut self, enabled: bool) -> Self {
+        self.normalize_punctuation = enabled;
+        self
+    }
+
+    // Rejoin words hyphenated at the end of a line, as in text extracted from
+    // PDFs; see `rejoin_hyphenated_lines`
+    pub fn with_line_rejoining(mut self, enabled: bool) -> Self {
+        self.rejoin_lines = enabled;
+        self
+    }
+
+    // For Chinese and Japanese, which are written without spaces: each Han,
+    // hiragana or katakana character counts as a word, "。！？" also end
+    // sentences, and the syllable-based scores (Gunning Fog, both Flesch
+    // scores, SMOG, Linsear Write and FORCAST) are 0.0, or None for SMOG.
+    // The letter-based scores are still reported, though they are
+    // calibrated for English. See `is_mostly_cjk` to pick the mode per text.
+    pub fn with_cjk_mode(mut self, enabled: bool) -> Self {
+        self.cjk = enabled;
+        self
+    }
+
+    // Drop citation and footnote markers from academic text; see
+    // `strip_citations`
+    pub fn with_citation_stripping(mut self, citations: CitationStripping) -> Self {
+        self.citations = citations;
+        self
+    }
+
+    // Score only the prose of technical documents; see `strip_code`
+    pub fn with_code_exclusion(mut self, enabled: bool) -> Self {
+        self.exclude_code = enabled;
+        self
+    }
+
+    // Replace undecodable bytes in files with U+FFFD instead of failing
+    pub fn with_lossy_decoding(mut self, enabled: bool) -> Self {
+        self.lossy_decoding = enabled;
+        self
+    }
+
+    // Applies the configured clean-up before any counting
+    fn prepare<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = self.normalize(text);
+        if self.strip_html {
+            text = Cow::Owned(strip_html(&text));
+        }
+        if self.exclude_code {
+            text = Cow::Owned(strip_code(&text));
+        }
+        if self.citations != CitationStripping::Off {
+            text = Cow::Owned(strip_citations(&text, self.citations == CitationStripping::All));
+        }
+        if self.strip_markdown {
+            text = Cow::Owned(strip_markdown(&text));
+        }
+        text
+    }
+
+    // The clean-up that works on any piece of a text, so streamed chunks get
+    // it too
+    fn normalize<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        if self.normalize_punctuation {
+            text = Cow::Owned(normalize_punctuation(&text));
+        }
+        // A chunk never ends between a line-end hyphen and the next line,
+        // since it is only cut at whitespace after a letter
+        if self.rejoin_lines {
+            text = Cow::Owned(rejoin_hyphenated_lines(&text));
+        }
+        text
+    }
+
+    fn count_syllables(&self, word: &str) -> usize {
+        self.count_syllables_explained(word).count
+    }
+
+    // The syllable count of `word` with the steps that produced it
+    pub fn count_syllables_explained(&self, word: &str) -> SyllableBreakdown {
+        // Tokens past the length limit are base64, hashes and the like rather
+        // than words; count them as a word of the limit's length would be
+        if word.chars().nth(self.max_word_length).is_some() {
+            return SyllableBreakdown {
+                count: (self.max_word_length / LETTERS_PER_SYLLABLE).max(1),
+                source: SyllableSource::LengthEstimate,
+                ..SyllableBreakdown::default()
+            };
+        }
+        let trimmed = word.trim_matches(|c: char| !c.is_alphabetic());
+        let cleaned_word = trimmed.to_lowercase();
+
+        // Handle special cases
+        if cleaned_word.is_empty() {
+            return SyllableBreakdown::default();
+        }
+        #[cfg(feature = "cmudict")]
+        if let Some(&count) = self.pronunciations.as_ref().and_then(|p| p.get(&cleaned_word)) {
+            return SyllableBreakdown { count, source: SyllableSource::Dictionary, ..SyllableBreakdown::default() };
+        }
+        // Each part of a compound keeps its own silent-e and other rules
+        if cleaned_word.contains(HYPHENS) {
+            let parts: Vec<SyllableBreakdown> =
+                trimmed.split(HYPHENS).map(|part| self.count_syllables_explained(part)).collect();
+            return SyllableBreakdown {
+                count: parts.iter().map(|part| part.count).sum(),
+                source: SyllableSource::Compound,
+                parts,
+                ..SyllableBreakdown::default()
+            };
+        }
+        // All-caps tokens without a vowel ("HTML", "BBC") are spelled out;
+        // ones with a vowel ("NASA") are read as words by the rules below
+        if trimmed.chars().nth(1).is_some()
+            && trimmed.chars().all(|c| c.is_uppercase())
+            && !cleaned_word.chars().any(|c| self.vowels.contains(c))
+        {
+            // Every letter name is one syllable except "double-u"
+            let count = cleaned_word.chars().map(|c| if c == 'w' { 3 } else { 1 }).sum();
+            return SyllableBreakdown { count, source: SyllableSource::Spelled, ..SyllableBreakdown::default() };
+        }
+        if self.affix_rules {
+            if let Some(stem) = self.suffix_stem(&cleaned_word) {
+                let parts = vec![
+                    self.count_syllables_explained(stem),
+                    self.count_syllables_explained(&cleaned_word[stem.len()..]),
+                ];
+                return SyllableBreakdown {
+                    count: parts.iter().map(|part| part.count).sum(),
+                    source: SyllableSource::Compound,
+                    parts,
+                    ..SyllableBreakdown::default()
+                };
+            }
+        }
+        let mut cleaned_word = self.split_hiatus(&self.split_vowel_endings(&cleaned_word));
+        if self.conditional_y {
+            cleaned_word = self.mark_consonant_y(&cleaned_word);
+        }
+
+        let vowel_groups = self.patterns.vowel.find_iter(&cleaned_word).count();
+
+        // Adjust for common patterns: a trailing "e" is silent, except in a
+        // consonant + "le" ending ("table", "bicycle") where it forms a syllable.
+        // A run of vowels is already a single group, so diphthongs ("queue",
+        // "beautiful") need no further adjustment.
+        let silent_e = cleaned_word.ends_with('e') && vowel_groups > 1 && !self.ends_with_consonant_le(&cleaned_word);
+        let count = vowel_groups - usize::from(silent_e);
+
+        // Ensure at least one syllable
+        SyllableBreakdown {
+            vowel_groups,
+            silent_e,
+            clamped: count == 0,
+            count: count.max(1),
+            ..SyllableBreakdown::default()
+        }
+    }
+
+    // A "y" at the start of a word or next to another vowel ("yellow", "boy",
+    // "player") is a consonant, so it is replaced by a non-vowel
+    fn mark_consonant_y(&self, word: &str) -> String {
+        let chars: Vec<char> = word.chars().collect();
+        let is_other_vowel = |i: Option<usize>| {
+            i.and_then(|i| chars.get(i)).is_some_and(|&c| c != 'y' && self.vowels.contains(c))
+        };
+        chars
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| {
+                let consonant = c == 'y' && (i == 0 || is_other_vowel(i.checked_sub(1)) || is_other_vowel(Some(i + 1)));
+                if consonant { '-' } else { c }
+            })
+            .collect()
+    }
+
+    // In "-eate" and "-iate" ("create", "mediate") the vowels before "ate"
+    // are separate syllables, so the pair is split to keep the silent-e rule
+    // from leaving a single group. A final "-ea" or "-ia" after a consonant
+    // is two syllables too ("area", "media") unless it is the only vowel
+    // group ("sea", "tea").
+    fn split_vowel_endings<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        for ending in ["eate", "iate"] {
+            if let Some(stem) = word.strip_suffix(ending) {
+                return Cow::Owned(format!("{}{}-ate", stem, &ending[..1]));
+            }
+        }
+        // "cre-ation", "vari-ation", "situ-ation"
+        if self.affix_rules {
+            if let Some(index) = word.find("ation").filter(|&index| index > 0) {
+                if word[..index].chars().last().is_some_and(|c| self.vowels.contains(c)) {
+                    return Cow::Owned(format!("{}-{}", &word[..index], &word[index..]));
+                }
+            }
+        }
+        for ending in ["ea", "ia"] {
+            if let Some(stem) = word.strip_suffix(ending) {
+                let after_consonant = stem.chars().last().is_some_and(|c| !self.vowels.contains(c));
+                if after_consonant && stem.chars().any(|c| self.vowels.contains(c)) {
+                    return Cow::Owned(format!("{}{}-a", stem, &ending[..1]));
+                }
+            }
+        }
+        Cow::Borrowed(word)
+    }
+
+    // The stem before a suffix that is a syllable of its own, if the stem
+    // has a vowel ("hope" in "hopeless", but nothing in "bless")
+    fn suffix_stem<'a>(&self, word: &'a str) -> Option<&'a str> {
+        let stem = ["ments", "ment", "ness", "less", "ful", "ly"]
+            .iter()
+            .find_map(|suffix| word.strip_suffix(suffix))?;
+        stem.chars().any(|c| self.vowels.contains(c)).then_some(stem)
+    }
+
+    // Breaks vowel runs before a diaeresis vowel so it forms its own group
+    fn split_hiatus(&self, word: &str) -> String {
+        let mut result = String::with_capacity(word.len());
+        let mut previous_is_vowel = false;
+        for c in word.chars() {
+            let is_vowel = self.vowels.contains(c);
+            if is_vowel && previous_is_vowel && DIAERESIS_VOWELS.contains(c) {
+                result.push('-');
+            }
+            result.push(c);
+            previous_is_vowel = is_vowel;
+        }
+        result
+    }
+
+    fn ends_with_consonant_le(&self, word: &str) -> bool {
+        let Some(stem) = word.strip_suffix("le") else {
+            return false;
+        };
+        stem.chars().last().is_some_and(|c| c.is_alphabetic() && !self.vowels.contains(c))
+    }
+
+    fn is_complex_word(&self, word: &str, syllable_count: usize) -> bool {
+        if syllable_count < self.complex_syllable_threshold {
+            return false;
+        }
+        let cleaned_word = word.trim_matches(|c: char| !c.is_alphabetic()).to_lowercase();
+        if self.complexity_exclusions.contains(&cleaned_word) {
+            return false;
+        }
+        if !self.exclude_inflections {
+            return true;
+        }
+        // An "-ed", "-es" or "-ing" ending that brings a word to the threshold
+        // is discounted only when it takes the place of a silent "e" after a
+        // short stem, as in "amusing" (amus-e). Full stems such as "proceed",
+        // "process" and "collect" keep their inflections complex, and so does
+        // a stem that reaches the threshold alone, as in "interested".
+        match ["ing", "ed", "es"].iter().find_map(|suffix| cleaned_word.strip_suffix(suffix)) {
+            Some(stem) => {
+                self.count_syllables(stem) >= self.complex_syllable_threshold || !ends_in_short_syllable(stem)
+            }
+            None => true,
+        }
+    }
+
+    // A word is difficult if it is missing from the loaded easy-word list
+    fn is_difficult_word(&self, word: &str) -> bool {
+        self.is_unlisted(&self.dale_chall_list, word)
+    }
+
+    fn is_unfamiliar_word(&self, word: &str) -> bool {
+        self.is_unlisted(&self.spache_list, word)
+    }
+
+    fn is_unlisted(&self, list: &Option<HashSet<String>>, word: &str) -> bool {
+        let Some(list) = list else {
+            return false;
+        };
+        let word = word.to_lowercase();
+        if list.contains(&word) {
+            return false;
+        }
+        !(self.list_stemming && inflection_stems(&word).iter().any(|stem| list.contains(stem)))
+    }
+
+    pub fn analyze_text(&self, text: &str) -> TextMetrics {
+        let counts = self.count(text);
+        self.text_metrics(text, &counts)
+    }
+
+    // Just the counts, without running any formula
+    pub fn count(&self, text: &str) -> RawCounts {
+        let text = self.prepare(text);
+        let mut counts = RawCounts::default();
+        self.accumulate(&mut counts, &text);
+        counts
+    }
+
+    // The in-memory counterpart of `analyze_files`; results keep the order of `docs`
+    pub fn analyze_many(&self, docs: &[&str]) -> Vec<TextMetrics> {
+        docs.iter().map(|doc| self.analyze_text(doc)).collect()
+    }
+
+    // Same as `analyze_many`, but documents are analyzed concurrently
+    #[cfg(feature = "rayon")]
+    pub fn par_analyze_many(&self, docs: &[&str]) -> Vec<TextMetrics> {
+        use rayon::prelude::*;
+        docs.par_iter().map(|doc| self.analyze_text(doc)).collect()
+    }
+
+    // Compares a draft `a` with its revision `b`
+    pub fn compare(&self, a: &str, b: &str) -> MetricsDiff {
+        MetricsDiff::new(&self.analyze_text(a), &self.analyze_text(b))
+    }
+
+    // Summarizes documents analyzed with this analyzer. Averaging the scores
+    // would weight a one-line note like a whole chapter, so `combined`
+    // recomputes every index, custom formulas included, from the summed
+    // counts. SMOG is always the approximate form there, as the sampled one
+    // needs the text.
+    pub fn aggregate(&self, metrics: &[TextMetrics]) -> CorpusSummary {
+        let mut totals = RawCounts::default();
+        for document in metrics {
+            let counts = document.raw_counts();
+            totals.word_count += counts.word_count;
+            totals.sentence_count += counts.sentence_count;
+            totals.syllable_count += counts.syllable_count;
+            totals.complex_word_count += counts.complex_word_count;
+            totals.difficult_word_count += counts.difficult_word_count;
+            totals.unfamiliar_word_count += counts.unfamiliar_word_count;
+            totals.long_word_count += counts.long_word_count;
+            totals.polysyllable_count += counts.polysyllable_count;
+            totals.monosyllable_count += counts.monosyllable_count;
+            totals.mini_word_count += counts.mini_word_count;
+            totals.letter_count += counts.letter_count;
+            totals.alphanumeric_count += counts.alphanumeric_count;
+            totals.total_char_count += counts.total_char_count;
+        }
+
+        let columns: Vec<Vec<Option<f64>>> = metrics.iter().map(TextMetrics::column_values).collect();
+        let fields = TextMetrics::CSV_COLUMNS
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let values: Vec<f64> = columns.iter().filter_map(|values| values[i]).collect();
+                let mean = (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64);
+                let std_dev = mean.map(|mean| {
+                    let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / values.len() as f64;
+                    variance.sqrt()
+                });
+                FieldSpread { field: field.to_string(), mean, std_dev }
+            })
+            .collect();
+
+        CorpusSummary {
+            documents: metrics.len(),
+            combined: self.metrics_from_counts(&totals),
+            fields,
+        }
+    }
+
+    // Unlike `analyze_text`, refuses input where the scores would rest on the
+    // one-sentence fallback or have no words to measure.
+    pub fn try_analyze_text(&self, text: &str) -> Result<TextMetrics, AnalysisError> {
+        let counts = self.count(text);
+        if counts.word_count == 0 {
+            return Err(AnalysisError::EmptyInput);
+        }
+        if counts.sentence_count == 0 {
+            return Err(AnalysisError::NoSentences);
+        }
+        Ok(self.text_metrics(text, &counts))
+    }
+
+    // `metrics_from_counts` plus the scores that need the text itself
+    fn text_metrics(&self, text: &str, counts: &RawCounts) -> TextMetrics {
+        let mut metrics = self.metrics_from_counts(counts);
+        if self.smog_mode == SmogMode::Sampled && metrics.smog_index.is_some() {
+            metrics.smog_index = Some(self.sampled_smog(&self.prepare(text)));
+        }
+        metrics
+    }
+
+    // Expects at least 30 sentences
+    fn sampled_smog(&self, text: &str) -> f64 {
+        let sentences = self.split_sentences(text);
+        let middle = sentences.len() / 2 - 5;
+        let sample = sentences[..10]
+            .iter()
+            .chain(&sentences[middle..middle + 10])
+            .chain(&sentences[sentences.len() - 10..]);
+        let polysyllables = sample
+            .flat_map(|sentence| self.patterns.word.find_iter(sentence))
+            .filter(|word| self.count_syllables(word.as_str()) >= 3)
+            .count();
+        1.0430 * f64::sqrt(polysyllables as f64) + 3.1291
+    }
+
+    // Adds the counts for `text` to `counts`. Splitting input at whitespace
+    // and accumulating each piece gives the same totals as the whole text.
+    fn accumulate(&self, counts: &mut RawCounts, text: &str) {
+        self.accumulate_selected(counts, text, MetricSet::ALL);
+    }
+
+    // `accumulate`, skipping the per-word work that none of `metrics` needs.
+    // Counts that are skipped stay at 0.
+    fn accumulate_selected(&self, counts: &mut RawCounts, text: &str, metrics: MetricSet) {
+        let needs_syllables = metrics.intersects(MetricSet::SYLLABLE_BASED) && !self.cjk;
+        let needs_complex = metrics.contains(MetricSet::GUNNING_FOG);
+        let needs_difficult = metrics.contains(MetricSet::DALE_CHALL) && self.dale_chall_list.is_some();
+        let needs_unfamiliar = metrics.contains(MetricSet::SPACHE) && self.spache_list.is_some();
+        let sentences = self.sentence_boundaries(text).len();
+        log::debug!("{} sentence boundaries in {} bytes", sentences, text.len());
+        counts.sentence_count += sentences;
+        let (mut letters, mut cjk_letters) = (0, 0);
+        for c in text.chars() {
+            counts.total_char_count += 1;
+            if c.is_alphanumeric() {
+                counts.alphanumeric_count += 1;
+            }
+            if c.is_alphabetic() {
+                letters += 1;
+                if is_cjk(c) {
+                    cjk_letters += 1;
+                }
+            }
+        }
+        counts.letter_count += letters;
+        if !self.cjk && cjk_letters * 2 > letters {
+            CJK_WARNING.call_once(|| {
+                log::warn!("text looks like Chinese or Japanese; word counts and syllable-based scores need CJK mode")
+            });
+        }
+
+        let words = self.patterns.word.find_iter(text).map(|m| m.as_str());
+        let words: Box<dyn Iterator<Item = &str>> =
+            if self.cjk { Box::new(words.flat_map(split_cjk)) } else { Box::new(words) };
+        for word in words {
+            counts.word_count += 1;
+            match word.chars().count() {
+                length if length > 6 => counts.long_word_count += 1,
+                length if length <= 3 => counts.mini_word_count += 1,
+                _ => {}
+            }
+            if needs_difficult && self.is_difficult_word(word) {
+                log::trace!("difficult word {:?}", word);
+                counts.difficult_word_count += 1;
+            }
+            if needs_unfamiliar && self.is_unfamiliar_word(word) {
+                log::trace!("unfamiliar word {:?}", word);
+                counts.unfamiliar_word_count += 1;
+            }
+            if !needs_syllables {
+                continue;
+            }
+            let word_syllables = self.count_syllables(word);
+            log::trace!("word {:?}: {} syllables", word, word_syllables);
+            counts.syllable_count += word_syllables;
+            if needs_complex && self.is_complex_word(word, word_syllables) {
+                log::trace!("complex word {:?}", word);
+                counts.complex_word_count += 1;
+            }
+            if word_syllables >= 3 {
+                counts.polysyllable_count += 1;
+            }
+            if word_syllables == 1 {
+                counts.monosyllable_count += 1;
+            }
+        }
+
+        if self.numbers == NumberHandling::Spoken {
+            for number in self.patterns.number.find_iter(text) {
+                counts.word_count += 1;
+                if !needs_syllables {
+                    continue;
+                }
+                let syllables = spoken_number_syllables(number.as_str());
+                log::trace!("number {:?}: {} syllables spoken", number.as_str(), syllables);
+                counts.syllable_count += syllables;
+                if syllables >= 3 {
+                    counts.polysyllable_count += 1;
+                }
+                if syllables == 1 {
+                    counts.monosyllable_count += 1;
+                }
+            }
+        }
+    }
+
+    fn metrics_from_counts(&self, counts: &RawCounts) -> TextMetrics {
+        // Every formula sees at least one sentence, as the averages do
+        if counts.sentence_count == 0 && counts.word_count > 0 {
+            log::debug!("no sentence terminators found; scoring the text as one sentence");
+        }
+        let counts = RawCounts {
+            sentence_count: counts.sentence_count.max(1),
+            ..*counts
+        };
+        let RawCounts {
+            word_count,
+            sentence_count,
+            syllable_count,
+            complex_word_count,
+            difficult_word_count,
+            unfamiliar_word_count,
+            long_word_count,
+            polysyllable_count,
+            monosyllable_count,
+            mini_word_count,
+            letter_count,
+            alphanumeric_count,
+            total_char_count,
+        } = counts;
+
+        // Both averages are 0.0 rather than NaN when there is nothing to
+        // divide; use `try_analyze_text` to tell such input apart
+        let average_words_per_sentence = ratio(word_count, sentence_count);
+        let average_syllables_per_word = ratio(syllable_count, word_count);
+        // CJK mode counts no syllables
+        let syllable_based = |formula: &dyn ReadabilityFormula| if self.cjk { 0.0 } else { formula.score(&counts) };
+
+        TextMetrics {
+            word_count,
+            sentence_count,
+            syllable_count,
+            complex_word_count,
+            difficult_word_count,
+            unfamiliar_word_count,
+            long_word_count,
+            polysyllable_count,
+            monosyllable_count,
+            mini_word_count,
+            letter_count,
+            alphanumeric_count,
+            total_char_count,
+            gunning_fog_index: syllable_based(&GunningFog),
+            flesch_kincaid_grade: syllable_based(&FleschKincaidGrade),
+            flesch_reading_ease: syllable_based(&FleschReadingEase),
+            // SMOG is only valid for 30+ sentences
+            smog_index: (sentence_count >= 30 && !self.cjk).then(|| Smog.score(&counts)),
+            coleman_liau_index: ColemanLiau.score(&counts),
+            automated_readability_index: AutomatedReadability.score(&counts),
+            dale_chall_score: if self.dale_chall_list.is_some() { DaleChall.score(&counts) } else { 0.0 },
+            spache_score: if self.spache_list.is_some() { Spache.score(&counts) } else { 0.0 },
+            lix_index: Lix.score(&counts),
+            rix_index: Rix.score(&counts),
+            linsear_write: syllable_based(&LinsearWrite),
+            forcast_index: syllable_based(&Forcast),
+            eflaw_score: Eflaw.score(&counts),
+            average_words_per_sentence,
+            average_syllables_per_word,
+            custom: self
+                .formulas
+                .iter()
+                .map(|formula| (formula.name().to_string(), formula.score(&counts)))
+                .collect(),
+        }
+    }
+
+    // Number of words with each syllable count
+    pub fn syllable_histogram(&self, text: &str) -> BTreeMap<usize, usize> {
+        let text = self.prepare(text);
+        let mut histogram = BTreeMap::new();
+        for word in self.patterns.word.find_iter(&text) {
+            *histogram.entry(self.count_syllables(word.as_str())).or_insert(0) += 1;
+        }
+        histogram
+    }
+
+    pub fn word_statistics(&self, text: &str) -> WordStats {
+        let text = self.prepare(text);
+        let mut frequencies: HashMap<String, usize> = HashMap::new();
+        // Count of each casing and the order it was first seen in
+        let mut surfaces: HashMap<&str, (usize, usize)> = HashMap::new();
+        let mut longest_word: Option<&str> = None;
+        let mut total = 0;
+
+        for word in self.patterns.word.find_iter(&text).map(|m| m.as_str()) {
+            total += 1;
+            *frequencies.entry(word.to_lowercase()).or_insert(0) += 1;
+            if self.surface_forms {
+                let order = surfaces.len();
+                surfaces.entry(word).or_insert((0, order)).0 += 1;
+            }
+            if longest_word.is_none_or(|longest| word.chars().count() > longest.chars().count()) {
+                longest_word = Some(word);
+            }
+        }
+
+        let unique_words = frequencies.len();
+        let mut most_frequent: Vec<(String, usize)> = frequencies.into_iter().collect();
+        most_frequent.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        most_frequent.truncate(TOP_WORDS);
+        if self.surface_forms {
+            let mut best: HashMap<String, (&str, usize, usize)> = HashMap::new();
+            for (&surface, &(count, order)) in &surfaces {
+                let key = surface.to_lowercase();
+                let rank = |(form, count, order): (&str, usize, usize)| (count, form == key, Reverse(order));
+                if best.get(&key).is_none_or(|&current| rank((surface, count, order)) > rank(current)) {
+                    best.insert(key, (surface, count, order));
+                }
+            }
+            for (word, _) in &mut most_frequent {
+                *word = best[word.as_str()].0.to_string();
+            }
+        }
+
+        WordStats {
+            most_frequent,
+            longest_word: longest_word.map(str::to_string),
+            unique_words,
+            type_token_ratio: if total > 0 { unique_words as f64 / total as f64 } else { 0.0 },
+        }
+    }
+
+    // All zero for a text without sentences
+    pub fn sentence_length_stats(&self, text: &str) -> SentenceLengthStats {
+        let text = self.prepare(text);
+        let lengths: Vec<usize> = self
+            .split_sentences(&text)
+            .into_iter()
+            .map(|sentence| self.patterns.word.find_iter(sentence).count())
+            .collect();
+        let (Some(&min), Some(&max)) = (lengths.iter().min(), lengths.iter().max()) else {
+            return SentenceLengthStats::default();
+        };
+        let mean = ratio(lengths.iter().sum(), lengths.len());
+        let variance = lengths.iter().map(|&length| (length as f64 - mean).powi(2)).sum::<f64>() / lengths.len() as f64;
+        SentenceLengthStats { mean, min, max, std_dev: variance.sqrt() }
+    }
+
+    // Byte offsets just past each sentence terminator
+    fn sentence_boundaries(&self, text: &str) -> Vec<usize> {
+        let addresses: Vec<_> = self.patterns.address.find_iter(text).map(|m| m.range()).collect();
+        let mut terminators: Vec<_> = self.patterns.sentence.find_iter(text).collect();
+        if self.cjk {
+            terminators.extend(self.patterns.cjk_sentence.find_iter(text));
+            terminators.sort_by_key(|m| m.start());
+        }
+        terminators
+            .into_iter()
+            .filter(|m| !addresses.iter().any(|address| address.contains(&m.start())))
+            .filter(|m| !Self::is_non_terminal_period(text, m.start(), m.end()))
+            .filter(|m| !Self::is_mid_sentence_ellipsis(text, m.start(), m.end()))
+            .map(|m| Self::skip_closing_punctuation(text, m.end()))
+            .collect()
+    }
+
+    // "He paused... then left." continues after the ellipsis
+    fn is_mid_sentence_ellipsis(text: &str, start: usize, end: usize) -> bool {
+        let terminator = &text[start..end];
+        terminator.len() > 1
+            && terminator.chars().all(|c| c == '.')
+            && text[end..].trim_start().chars().next().is_some_and(char::is_lowercase)
+    }
+
+    // Keeps closing quotes and brackets with the sentence they end: `said "go."`
+    fn skip_closing_punctuation(text: &str, end: usize) -> usize {
+        let rest = &text[end..];
+        let closing = rest.len() - rest.trim_start_matches(CLOSING_PUNCTUATION).len();
+        end + closing
+    }
+
+    fn is_non_terminal_period(text: &str, start: usize, end: usize) -> bool {
+        if &text[start..end] != "." {
+            return false;
+        }
+        let before = text[..start].chars().next_back();
+        let after = text[end..].chars().next();
+
+        // Decimal numbers like "3.14"
+        if before.is_some_and(|c| c.is_ascii_digit()) && after.is_some_and(|c| c.is_ascii_digit()) {
+            return true;
+        }
+        // Inner periods of dotted abbreviations like "U.S.A."
+        if before.is_some_and(char::is_alphabetic) && after.is_some_and(char::is_alphabetic) {
+            return true;
+        }
+
+        let token = text[..start]
+            .rsplit(|c: char| !(c.is_alphabetic() || c == '.'))
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+        let numbered = NUMBERED_ABBREVIATIONS.contains(&token.as_str())
+            && text[end..].trim_start().starts_with(|c: char| c.is_ascii_digit());
+        numbered || ABBREVIATIONS.contains(&token.as_str())
+    }
+
+    fn split_sentences<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        self.sentence_spans(text).into_iter().map(|(_, sentence)| sentence).collect()
+    }
+
+    // The sentences of `text` with their byte offsets
+    fn sentence_spans<'a>(&self, text: &'a str) -> Vec<(usize, &'a str)> {
+        let mut sentences = Vec::new();
+        let mut start = 0;
+        for end in self.sentence_boundaries(text).into_iter().chain([text.len()]) {
+            let piece = &text[start..end];
+            let sentence = piece.trim();
+            if !sentence.is_empty() {
+                sentences.push((start + piece.len() - piece.trim_start().len(), sentence));
+            }
+            start = end;
+        }
+        sentences
+    }
+
+    // Paragraphs are separated by one or more blank lines. Blank lines inside
+    // a ``` or ~~~ fenced block don't end one, so the block stays whole for
+    // the code and Markdown stripping.
+    pub fn analyze_paragraphs(&self, text: &str) -> Vec<TextMetrics> {
+        let mut paragraphs = Vec::new();
+        let mut current: Vec<&str> = Vec::new();
+        let mut fence: Option<&str> = None;
+        // `lines` also strips the "\r" of Windows line endings
+        for line in text.lines() {
+            let trimmed = line.trim_start();
+            match fence {
+                Some(marker) if trimmed.starts_with(marker) => fence = None,
+                None if trimmed.starts_with("```") => fence = Some("```"),
+                None if trimmed.starts_with("~~~") => fence = Some("~~~"),
+                _ => {}
+            }
+            if !line.trim().is_empty() || fence.is_some() {
+                current.push(line);
+            } else if !current.is_empty() {
+                paragraphs.push(self.analyze_text(&current.join("\n")));
+                current.clear();
+            }
+        }
+        // An unclosed fence runs to the end
+        if !current.is_empty() {
+            paragraphs.push(self.analyze_text(&current.join("\n")));
+        }
+        paragraphs
+    }
+
+    pub fn analyze_sentences(&self, text: &str) -> Vec<SentenceMetrics> {
+        let text = self.prepare(text);
+        // Lines and columns are counted on from the previous sentence
+        let (mut position, mut line, mut column) = (0, 1, 1);
+        self.sentence_spans(&text)
+            .into_iter()
+            .map(|(offset, sentence)| {
+                for c in text[position..offset].chars() {
+                    if c == '\n' {
+                        line += 1;
+                        column = 1;
+                    } else {
+                        column += 1;
+                    }
+                }
+                position = offset;
+                let words: Vec<&str> = self.patterns.word.find_iter(sentence)
+                    .map(|m| m.as_str())
+                    .collect();
+                let syllable_count = words.iter().map(|w| self.count_syllables(w)).sum();
+                SentenceMetrics {
+                    text: sentence.to_string(),
+                    offset,
+                    line,
+                    column,
+                    word_count: words.len(),
+                    syllable_count,
+                    flesch_reading_ease: FleschReadingEase.score(&RawCounts {
+                        word_count: words.len(),
+                        sentence_count: 1,
+                        syllable_count,
+                        ..RawCounts::default()
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    // Every score for each sentence on its own, in order. The text is
+    // cleaned up once, so sentences are counted as they appear in it.
+    pub fn score_sentences(&self, text: &str) -> Vec<(String, TextMetrics)> {
+        let text = self.prepare(text);
+        self.split_sentences(&text)
+            .into_iter()
+            .map(|sentence| {
+                let mut counts = RawCounts::default();
+                self.accumulate(&mut counts, sentence);
+                (sentence.to_string(), self.metrics_from_counts(&counts))
+            })
+            .collect()
+    }
+
+    // The `n` sentences with the highest Flesch-Kincaid grade, hardest first;
+    // of two sentences with the same grade the one with more words comes first
+    pub fn hardest_sentences(&self, text: &str, n: usize) -> Vec<(f64, String)> {
+        let mut graded: Vec<(f64, usize, String)> = self
+            .score_sentences(text)
+            .into_iter()
+            .map(|(sentence, metrics)| (metrics.flesch_kincaid_grade, metrics.word_count, sentence))
+            .collect();
+        graded.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| b.1.cmp(&a.1)));
+        graded.into_iter().take(n).map(|(grade, _, text)| (grade, text)).collect()
+    }
+
+    // Words repeated back to back, such as "the the", as the byte offset of
+    // the repetition in the prepared text and the word as written there.
+    // Case and punctuation between the two are ignored, but a sentence
+    // boundary is not, so "Bye. Bye." is left alone.
+    pub fn repeated_words(&self, text: &str) -> Vec<(usize, String)> {
+        let text = self.prepare(text);
+        let boundaries = self.sentence_boundaries(&text);
+        let mut repeated = Vec::new();
+        let mut previous: Option<regex::Match> = None;
+        for word in self.patterns.word.find_iter(&text) {
+            if let Some(previous) = previous {
+                let same_sentence = !boundaries.iter().any(|&end| previous.end() < end && end <= word.start());
+                if same_sentence && previous.as_str().to_lowercase() == word.as_str().to_lowercase() {
+                    repeated.push((word.start(), word.as_str().to_string()));
+                }
+            }
+            previous = Some(word);
+        }
+        repeated
+    }
+
+    // Fraction of sentences with a form of "to be" followed by a past
+    // participle, optionally with one "-ly" adverb or "not" between them, as in
+    // "was thrown" or "is being quickly reviewed". Participles are words
+    // ending in "-ed" plus a list of common irregular ones. This is a
+    // heuristic: it misses passives formed with "get" ("got fired") and
+    // irregular participles outside the list, and flags adjectives that look
+    // like participles ("was tired", "is interested").
+    pub fn passive_voice_ratio(&self, text: &str) -> f64 {
+        let text = self.prepare(text);
+        let sentences = self.split_sentences(&text);
+        let passive = sentences.iter().filter(|sentence| self.is_passive(sentence)).count();
+        ratio(passive, sentences.len())
+    }
+
+    fn is_passive(&self, sentence: &str) -> bool {
+        let words: Vec<String> = self.patterns.word.find_iter(sentence).map(|m| m.as_str().to_lowercase()).collect();
+        words.iter().enumerate().any(|(i, word)| {
+            if !BE_FORMS.contains(&word.as_str()) {
+                return false;
+            }
+            let mut following = words[i + 1..].iter().map(String::as_str);
+            let mut next = following.next();
+            if next.is_some_and(|w| w == "not" || w.ends_with("ly")) {
+                next = following.next();
+            }
+            next.is_some_and(|w| (w.len() > 3 && w.ends_with("ed")) || IRREGULAR_PARTICIPLES.contains(&w))
+        })
+    }
+
+    pub fn calculate_forcast(&self, text: &str) -> f64 {
+        let metrics = self.analyze_text(text);
+        metrics.forcast_index
+    }
+
+    pub fn calculate_eflaw(&self, text: &str) -> f64 {
+        let metrics = self.analyze_text(text);
+        metrics.eflaw_score
+    }
+
+    // RIX from counts already taken, such as `long_word_count` and
+    // `sentence_count`; 0.0 when there are no sentences
+    pub fn calculate_rix(&self, long_words: usize, sentences: usize) -> f64 {
+        formula::rix(long_words, sentences)
+    }
+
+    // Syllables and sentences per 100 words, the two axes of the Fry graph
+    pub fn fry_coordinates(&self, text: &str) -> (f64, f64) {
+        let counts = self.count(text);
+        if counts.word_count == 0 {
+            return (0.0, 0.0);
+        }
+        let per_100_words = 100.0 / counts.word_count as f64;
+        (
+            counts.syllable_count as f64 * per_100_words,
+            counts.sentence_count.max(1) as f64 * per_100_words,
+        )
+    }
+
+    // Grade (1-12, 13 for college) of the Fry graph region nearest to the
+    // text's coordinates. The graph's sentence axis is roughly logarithmic,
+    // so distances are measured against ln(sentences).
+    pub fn fry_grade(&self, text: &str) -> u32 {
+        let (syllables, sentences) = self.fry_coordinates(text);
+        let distance = |&(_, center_syllables, center_sentences): &(u32, f64, f64)| {
+            let dx = (syllables - center_syllables) / (FRY_SYLLABLES.1 - FRY_SYLLABLES.0);
+            let dy = (sentences.max(FRY_SENTENCES.0).ln() - f64::ln(center_sentences))
+                / (FRY_SENTENCES.1.ln() - FRY_SENTENCES.0.ln());
+            dx * dx + dy * dy
+        };
+        FRY_GRADES
+            .iter()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .map_or(1, |&(grade, _, _)| grade)
+    }
+
+    // Sentences and long words per 100 words, the two axes of the Raygor
+    // graph. Raygor's long words have 6 or more letters, one fewer than the
+    // LIX and RIX ones, so they are counted here rather than taken from
+    // `long_word_count`. The graph is for English words, so CJK mode, where
+    // single characters are words, gives (0.0, 0.0).
+    pub fn raygor_coordinates(&self, text: &str) -> (f64, f64) {
+        if self.cjk {
+            return (0.0, 0.0);
+        }
+        let text = self.prepare(text);
+        let (mut words, mut long_words) = (0, 0);
+        for word in self.patterns.word.find_iter(&text) {
+            words += 1;
+            if word.as_str().chars().filter(|c| c.is_alphabetic()).count() >= 6 {
+                long_words += 1;
+            }
+        }
+        if self.numbers == NumberHandling::Spoken {
+            words += self.patterns.number.find_iter(&text).count();
+        }
+        if words == 0 {
+            return (0.0, 0.0);
+        }
+        let sentences = self.sentence_boundaries(&text).len().max(1);
+        let per_100_words = 100.0 / words as f64;
+        (sentences as f64 * per_100_words, long_words as f64 * per_100_words)
+    }
+
+    // Grade (3-12, 13 for college, 14 for professional) of the Raygor graph
+    // region nearest to the text's coordinates, measured as for `fry_grade`
+    pub fn raygor_grade(&self, text: &str) -> u32 {
+        let (sentences, long_words) = self.raygor_coordinates(text);
+        let distance = |&(_, center_long_words, center_sentences): &(u32, f64, f64)| {
+            let dx = (sentences.max(RAYGOR_SENTENCES.0).ln() - f64::ln(center_sentences))
+                / (RAYGOR_SENTENCES.1.ln() - RAYGOR_SENTENCES.0.ln());
+            let dy = (long_words - center_long_words) / (RAYGOR_LONG_WORDS.1 - RAYGOR_LONG_WORDS.0);
+            dx * dx + dy * dy
+        };
+        RAYGOR_GRADES
+            .iter()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .map_or(3, |&(grade, _, _)| grade)
+    }
+
+    pub fn calculate_dale_chall(&self, text: &str) -> f64 {
+        let metrics = self.analyze_text(text);
+        metrics.dale_chall_score
+    }
+
+    pub fn calculate_spache(&self, text: &str) -> f64 {
+        let metrics = self.analyze_text(text);
+        metrics.spache_score
+    }
+}
+
+// Reading from files and streams; there is no filesystem under wasm
+#[cfg(not(target_arch = "wasm32"))]
+impl TextAnalyzer {
+    pub fn analyze_file(&self, filepath: &str) -> Result<TextMetrics, AnalyzerError> {
+        let path = Path::new(filepath);
+        if !path.exists() {
+            return Err(AnalyzerError::NotFound(path.to_path_buf()));
+        }
+        match &self.cache {
+            Some(cache) => cache.get_or_analyze(path, || self.read_file(path)),
+            None => self.read_file(path),
+        }
+    }
+
+    fn read_file(&self, path: &Path) -> Result<TextMetrics, AnalyzerError> {
+        self.analyze_reader(Self::open_file(path)?)
+    }
+
+    // The whole text of a file, decompressed and decoded as `analyze_file`
+    // reads it, for work that needs the text itself such as
+    // `analyze_sentences`
+    pub fn read_text(&self, filepath: &str) -> Result<String, AnalyzerError> {
+        let path = Path::new(filepath);
+        if !path.exists() {
+            return Err(AnalyzerError::NotFound(path.to_path_buf()));
+        }
+        let mut bytes = Vec::new();
+        Self::open_file(path)?.read_to_end(&mut bytes)?;
+        if bytes.starts_with(UTF16_LE_BOM) || bytes.starts_with(UTF16_BE_BOM) {
+            return Ok(self.decode_utf16(&bytes)?);
+        }
+        let offset = if bytes.starts_with(UTF8_BOM) { UTF8_BOM.len() } else { 0 };
+        Ok(self.decode_utf8(&bytes[offset..], offset)?.into_owned())
+    }
+
+    fn open_file(path: &Path) -> io::Result<Box<dyn Read>> {
+        let file = File::open(path)?;
+        #[cfg(feature = "gzip")]
+        {
+            // Detected by the magic bytes, so the file name doesn't matter
+            let mut reader = BufReader::new(file);
+            if reader.fill_buf()?.starts_with(GZIP_MAGIC) {
+                log::debug!("{}: decompressing gzip", path.display());
+                return Ok(Box::new(flate2::bufread::MultiGzDecoder::new(reader)));
+            }
+            Ok(Box::new(reader))
+        }
+        #[cfg(not(feature = "gzip"))]
+        Ok(Box::new(file))
+    }
+
+    // Analyzes anything readable, such as a socket, a decompressor or an
+    // in-memory cursor, with the same decoding and chunking as `analyze_file`
+    pub fn analyze_reader<R: Read>(&self, reader: R) -> Result<TextMetrics, AnalyzerError> {
+        let metrics = self.analyze_stream(reader, CHUNK_SIZE)?;
+        if metrics.word_count == 0 {
+            return Err(AnalyzerError::EmptyInput);
+        }
+        Ok(metrics)
+    }
+
+    pub fn analyze_files(&self, paths: &[String]) -> Vec<(String, Result<TextMetrics, AnalyzerError>)> {
+        self.analyze_files_with_progress(paths, |_, _, _| {})
+    }
+
+    // Same as `analyze_files`, calling `progress(index, total, path)` just
+    // before each file is analyzed, with `index` counting from 0
+    pub fn analyze_files_with_progress<F: FnMut(usize, usize, &str)>(
+        &self,
+        paths: &[String],
+        mut progress: F,
+    ) -> Vec<(String, Result<TextMetrics, AnalyzerError>)> {
+        paths
+            .iter()
+            .enumerate()
+            .map(|(index, path)| {
+                progress(index, paths.len(), path);
+                (path.clone(), self.analyze_file(path))
+            })
+            .collect()
+    }
+
+    // Same as `analyze_files`, but files are analyzed concurrently. Results
+    // keep the order of `paths`.
+    #[cfg(feature = "rayon")]
+    pub fn par_analyze_files(&self, paths: &[String]) -> Vec<(String, Result<TextMetrics, AnalyzerError>)> {
+        use rayon::prelude::*;
+        paths.par_iter().map(|path| (path.clone(), self.analyze_file(path))).collect()
+    }
+
+    // Analyzes every `.txt` file in `dir`, sorted by path. A file that fails
+    // to read is reported in its own entry instead of aborting the walk; only
+    // an unreadable top-level `dir` is an error.
+    pub fn analyze_directory(
+        &self,
+        dir: &str,
+        recursive: bool,
+    ) -> io::Result<Vec<(String, Result<TextMetrics, AnalyzerError>)>> {
+        self.analyze_directory_with_progress(dir, recursive, |_, _, _| {})
+    }
+
+    // Same as `analyze_directory`, with a progress callback as in
+    // `analyze_files_with_progress`. The whole tree is listed first, so
+    // `total` is known from the first call.
+    pub fn analyze_directory_with_progress<F: FnMut(usize, usize, &str)>(
+        &self,
+        dir: &str,
+        recursive: bool,
+        mut progress: F,
+    ) -> io::Result<Vec<(String, Result<TextMetrics, AnalyzerError>)>> {
+        let mut entries = Vec::new();
+        Self::walk_directory(Path::new(dir), recursive, &mut entries)?;
+        let total = entries.iter().filter(|(_, error)| error.is_none()).count();
+        let mut index = 0;
+        let results = entries
+            .into_iter()
+            .map(|(name, error)| match error {
+                Some(error) => (name, Err(error.into())),
+                None => {
+                    progress(index, total, &name);
+                    index += 1;
+                    let metrics = self.analyze_file(&name);
+                    (name, metrics)
+                }
+            })
+            .collect();
+        Ok(results)
+    }
+
+    // Lists the `.txt` files under `dir`, and the subdirectories that could
+    // not be read along with their errors
+    fn walk_directory(
+        dir: &Path,
+        recursive: bool,
+        entries: &mut Vec<(String, Option<io::Error>)>,
+    ) -> io::Result<()> {
+        let mut paths = fs::read_dir(dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        paths.sort();
+
+        for path in paths {
+            let name = path.to_string_lossy().into_owned();
+            if path.is_dir() {
+                if recursive {
+                    if let Err(error) = Self::walk_directory(&path, recursive, entries) {
+                        entries.push((name, Some(error)));
+                    }
+                }
+            } else if path.extension().is_some_and(|ext| ext == "txt") {
+                entries.push((name, None));
+            }
+        }
+        Ok(())
+    }
+
+    // Clean-up steps that need to see the whole document at once
+    fn needs_whole_text(&self) -> bool {
+        self.strip_markdown
+            || self.strip_html
+            || self.exclude_code
+            || self.citations != CitationStripping::Off
+            || self.smog_mode == SmogMode::Sampled
+    }
+
+    // Reads `reader` in chunks without holding the whole text in memory.
+    // Each chunk is counted up to its last whitespace after a letter; the rest
+    // is carried over so no word, and no terminator together with the text
+    // that decides whether it ends a sentence, is split across chunks.
+    // UTF-16 input (detected by its byte-order mark) is decoded in one piece.
+    fn analyze_stream<R: Read>(&self, reader: R, chunk_size: usize) -> io::Result<TextMetrics> {
+        let mut reader = BufReader::with_capacity(chunk_size, reader);
+        let mut pending: Vec<u8> = Vec::new();
+        (&mut reader).take(3).read_to_end(&mut pending)?;
+
+        if pending.starts_with(UTF16_LE_BOM) || pending.starts_with(UTF16_BE_BOM) {
+            reader.read_to_end(&mut pending)?;
+            return Ok(self.analyze_text(&self.decode_utf16(&pending)?));
+        }
+        let mut offset = 0;
+        if pending.starts_with(UTF8_BOM) {
+            pending.drain(..UTF8_BOM.len());
+            offset = UTF8_BOM.len();
+        }
+        if self.needs_whole_text() {
+            reader.read_to_end(&mut pending)?;
+            return Ok(self.analyze_text(&self.decode_utf8(&pending, offset)?));
+        }
+
+        let mut counts = RawCounts::default();
+        let mut scanned = 0;
+        loop {
+            let chunk = reader.fill_buf()?;
+            if chunk.is_empty() {
+                break;
+            }
+            pending.extend_from_slice(chunk);
+            let consumed = chunk.len();
+            reader.consume(consumed);
+
+            if let Some(split) = chunk_split_point(&pending, scanned) {
+                self.accumulate(&mut counts, &self.normalize(&self.decode_utf8(&pending[..=split], offset)?));
+                pending.drain(..=split);
+                offset += split + 1;
+            }
+            scanned = pending.len();
+        }
+        self.accumulate(&mut counts, &self.normalize(&self.decode_utf8(&pending, offset)?));
+
+        Ok(self.metrics_from_counts(&counts))
+    }
+
+    // `offset` is the position of `bytes` in the whole input, for error reporting
+    fn decode_utf8<'a>(&self, bytes: &'a [u8], offset: usize) -> io::Result<Cow<'a, str>> {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Ok(Cow::Borrowed(text)),
+            Err(_) if self.lossy_decoding => Ok(String::from_utf8_lossy(bytes)),
+            Err(error) => Err(EncodingError::InvalidUtf8 { offset: offset + error.valid_up_to() }.into()),
+        }
+    }
+
+    // `bytes` starts with a UTF-16 byte-order mark
+    fn decode_utf16(&self, bytes: &[u8]) -> io::Result<String> {
+        let big_endian = bytes.starts_with(UTF16_BE_BOM);
+        let body = &bytes[UTF16_LE_BOM.len()..];
+        let units = body.chunks_exact(2).map(|pair| {
+            if big_endian {
+                u16::from_be_bytes([pair[0], pair[1]])
+            } else {
+                u16::from_le_bytes([pair[0], pair[1]])
+            }
+        });
+
+        let mut text = String::with_capacity(body.len() / 2);
+        for c in char::decode_utf16(units) {
+            match c {
+                Ok(c) => text.push(c),
+                Err(_) if self.lossy_decoding => text.push(char::REPLACEMENT_CHARACTER),
+                Err(_) => return Err(EncodingError::InvalidUtf16.into()),
+            }
+        }
+        if !body.len().is_multiple_of(2) {
+            if !self.lossy_decoding {
+                return Err(EncodingError::InvalidUtf16.into());
+            }
+            text.push(char::REPLACEMENT_CHARACTER);
+        }
+        Ok(text)
+    }
+}
+
+// Keeps running counts for text that arrives in pieces, e.g. from an editor.
+// The text after the last safe split point is held back until more arrives,
+// so a word or sentence end split across two pushes is counted once.
+// Markdown and HTML stripping are not applied to pushed text.
+pub struct IncrementalAnalyzer<'a> {
+    analyzer: &'a TextAnalyzer,
+    counts: RawCounts,
+    pending: String,
+}
+
+impl<'a> IncrementalAnalyzer<'a> {
+    pub fn new(analyzer: &'a TextAnalyzer) -> Self {
+        IncrementalAnalyzer {
+            analyzer,
+            counts: RawCounts::default(),
+            pending: String::new(),
+        }
+    }
+
+    pub fn push(&mut self, chunk: &str) {
+        let scanned = self.pending.len();
+        self.pending.push_str(chunk);
+        // The split point is always ASCII whitespace, so a char boundary
+        if let Some(split) = chunk_split_point(self.pending.as_bytes(), scanned) {
+            self.analyzer.accumulate(&mut self.counts, &self.analyzer.normalize(&self.pending[..=split]));
+            self.pending.drain(..=split);
+        }
+    }
+
+    // Metrics for everything pushed so far, counting the held-back tail as if
+    // the text ended there
+    pub fn metrics(&self) -> TextMetrics {
+        let mut counts = self.counts;
+        self.analyzer.accumulate(&mut counts, &self.analyzer.normalize(&self.pending));
+        self.analyzer.metrics_from_counts(&counts)
+    }
+}
+
+// Maps each word to the syllables in its shortest listed pronunciation
+// ("every" is listed with both three and two). Syllables are counted as
+// vowel phonemes, the ones carrying a stress digit.
+#[cfg(feature = "cmudict")]
+fn load_cmudict(path: &Path) -> io::Result<HashMap<String, usize>> {
+    let mut pronunciations = HashMap::new();
+    for line in std::fs::read_to_string(path)?.lines() {
+        if line.starts_with(";;;") {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let Some(entry) = parts.next() else {
+            continue;
+        };
+        // Alternative pronunciations are listed as "WORD(1)", "WORD(2)", ...
+        let word = entry.split('(').next().unwrap_or(entry).to_lowercase();
+        let syllables = parts.filter(|phoneme| phoneme.ends_with(|c: char| c.is_ascii_digit())).count();
+        pronunciations
+            .entry(word)
+            .and_modify(|count: &mut usize| *count = (*count).min(syllables))
+            .or_insert(syllables);
+    }
+    Ok(pronunciations)
+}
+
+// Han ideographs, hiragana and katakana. Hangul is left out, as Korean puts
+// spaces between words.
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}' | '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}' | '\u{FF66}'..='\u{FF9F}' | '\u{20000}'..='\u{2FA1F}')
+}
+
+// Whether most of the letters in `text` are Chinese or Japanese, in which
+// case it is better analyzed with `TextAnalyzer::with_cjk_mode`
+pub fn is_mostly_cjk(text: &str) -> bool {
+    let (letters, cjk) = text
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .fold((0, 0), |(letters, cjk), c| (letters + 1, cjk + usize::from(is_cjk(c))));
+    cjk * 2 > letters
+}
+
+// Splits a word into single CJK characters and runs of other letters:
+// "Rust言語" gives "Rust", "言" and "語"
+fn split_cjk(word: &str) -> impl Iterator<Item = &str> {
+    let mut rest = word;
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let end = if is_cjk(first) { first.len_utf8() } else { rest.find(is_cjk).unwrap_or(rest.len()) };
+        let (piece, tail) = rest.split_at(end);
+        rest = tail;
+        Some(piece)
+    })
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+// Whether `stem` ends in one vowel between consonants, like "amus" or
+// "decid", the stems of words that drop a silent "e" when inflected
+fn ends_in_short_syllable(stem: &str) -> bool {
+    let is_vowel = |c: &char| "aeiou".contains(*c);
+    let mut last = stem.chars().rev();
+    match (last.next(), last.next(), last.next()) {
+        (Some(consonant), Some(vowel), before) => {
+            !is_vowel(&consonant) && is_vowel(&vowel) && !before.is_some_and(|c| is_vowel(&c))
+        }
+        _ => false,
+    }
+}
+
+// Candidate base forms of an inflected word: "played" gives "play", "hoped"
+// gives "hope", "running" gives "run" and "carries" gives "carry". Stems
+// shorter than two letters are not tried.
+fn inflection_stems(word: &str) -> Vec<String> {
+    let mut stems = Vec::new();
+    for suffix in ["ing", "ed", "es", "s"] {
+        let Some(stem) = word.strip_suffix(suffix) else {
+            continue;
+        };
+        if stem.chars().count() < 2 {
+            continue;
+        }
+        stems.push(stem.to_string());
+        if suffix != "s" {
+            stems.push(format!("{}e", stem));
+        }
+        // "running" -> "run", "stopped" -> "stop"
+        let mut chars = stem.chars().rev();
+        if let (Some(last), Some(before)) = (chars.next(), chars.next()) {
+            if last == before {
+                stems.push(stem[..stem.len() - last.len_utf8()].to_string());
+            }
+        }
+        if suffix == "ed" || suffix == "es" {
+            if let Some(base) = stem.strip_suffix('i') {
+                stems.push(format!("{}y", base));
+            }
+        }
+    }
+    stems
+}
+
+// Syllables in the English reading of a numeral like "1,000" or "3.14"
+fn spoken_number_syllables(number: &str) -> usize {
+    // zero, one, ..., nine
+    const ONES: [usize; 10] = [2, 1, 1, 1, 1, 1, 1, 2, 1, 1];
+    // ten, eleven, ..., nineteen
+    const TEENS: [usize; 10] = [1, 3, 1, 2, 2, 2, 2, 3, 2, 2];
+    // twenty, thirty, ..., ninety
+    const TENS: [usize; 10] = [0, 0, 2, 2, 2, 2, 2, 3, 2, 2];
+    // "hundred", "thousand", "million", ... all have two
+    const SCALE: usize = 2;
+
+    let below_thousand = |n: u64| {
+        let (hundreds, rest) = ((n / 100) as usize, (n % 100) as usize);
+        let mut syllables = if hundreds > 0 { ONES[hundreds] + SCALE } else { 0 };
+        syllables += match rest {
+            0 => 0,
+            1..=9 => ONES[rest],
+            10..=19 => TEENS[rest - 10],
+            _ => TENS[rest / 10] + if rest % 10 > 0 { ONES[rest % 10] } else { 0 },
+        };
+        syllables
+    };
+    let digits = |digits: &str| -> usize {
+        digits.bytes().map(|d| ONES[(d - b'0') as usize]).sum()
+    };
+
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let whole = whole.replace(',', "");
+    let mut syllables = match whole.parse::<u64>() {
+        Ok(0) => ONES[0],
+        Ok(mut n) => {
+            let mut syllables = 0;
+            let mut scale = 0;
+            while n > 0 {
+                let group = n % 1000;
+                if group > 0 {
+                    syllables += below_thousand(group) + if scale > 0 { SCALE } else { 0 };
+                }
+                n /= 1000;
+                scale += 1;
+            }
+            syllables
+        }
+        // Too large to read as a quantity; read digit by digit
+        Err(_) => digits(&whole),
+    };
+    if !fraction.is_empty() {
+        // "point" and then each digit
+        syllables += 1 + digits(fraction);
+    }
+    syllables
+}
+
+// Only positions from `from` on are checked; earlier ones are known to hold
+// no split point, which keeps a long run without whitespace from being
+// rescanned on every chunk
+fn chunk_split_point(bytes: &[u8], from: usize) -> Option<usize> {
+    (from.max(1)..bytes.len()).rev().find(|&i| {
+        let previous = bytes[i - 1];
+        bytes[i].is_ascii_whitespace() && !previous.is_ascii_whitespace() && !previous.is_ascii_punctuation()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_matches_new() {
+        #[derive(Default)]
+        struct Wrapper {
+            analyzer: TextAnalyzer,
+        }
+        let text = "Extraordinary complications arise. The cat sat.";
+        assert_eq!(Wrapper::default().analyzer.analyze_text(text), TextAnalyzer::new().analyze_text(text));
+    }
+
+    #[test]
+    fn test_empty_text() {
+        let analyzer = TextAnalyzer::new();
+        let metrics = analyzer.analyze_text("");
+        assert_eq!(metrics.word_count, 0);
+        assert_eq!(metrics.gunning_fog_index, 0.0);
+    }
+
+    #[test]
+    fn test_try_analyze_text() {
+        let analyzer = TextAnalyzer::new();
+        assert_eq!(analyzer.try_analyze_text("").unwrap_err(), AnalysisError::EmptyInput);
+        assert_eq!(analyzer.try_analyze_text("?!").unwrap_err(), AnalysisError::EmptyInput);
+        assert_eq!(analyzer.try_analyze_text("no terminator here").unwrap_err(), AnalysisError::NoSentences);
+        let metrics = analyzer.try_analyze_text("The cat sat on the mat.").unwrap();
+        assert_eq!(metrics, analyzer.analyze_text("The cat sat on the mat."));
+    }
+
+    fn assert_all_finite(metrics: &TextMetrics) {
+        for (column, value) in TextMetrics::CSV_COLUMNS.iter().zip(metrics.csv_values()) {
+            if value.is_empty() {
+                continue;
+            }
+            let value: f64 = value.parse().unwrap();
+            assert!(value.is_finite(), "{} is {}", column, value);
+        }
+    }
+
+    #[test]
+    fn test_markdown_stripping() {
+        let text = "# Title\n\nThis is **bold** text with [a link](https://example.com/a/b).\n\n\
+                    ```rust\nlet map = HashMap::new();\n```\n";
+        let plain = TextAnalyzer::new().analyze_text(text);
+        let analyzer = TextAnalyzer::new().with_markdown_stripping(true);
+        let stripped = analyzer.analyze_text(text);
+        assert_eq!(stripped, TextAnalyzer::new().analyze_text(&strip_markdown(text)));
+        // Title, This, is, bold, text, with, a, link
+        assert_eq!(stripped.word_count, 8);
+        assert!(plain.word_count > stripped.word_count);
+        assert_eq!(analyzer.word_statistics("**bold**").most_frequent, [("bold".to_string(), 1)]);
+        assert_eq!(analyzer.analyze_stream(text.as_bytes(), 4).unwrap(), stripped);
+    }
+
+    #[test]
+    fn test_code_exclusion() {
+        let text = "## Usage\n\nCreate the map first.\n\n```rust\nlet mut map = HashMap::new();\n\
+                    map.insert(key, value);\n```\n\nThen read it back:\n\n    let found = map.get(&key);\n\n\
+                    That is all.\n";
+        let analyzer = TextAnalyzer::new().with_code_exclusion(true);
+        let metrics = analyzer.analyze_text(text);
+        // Usage, Create, the, map, first, Then, read, it, back, That, is, all
+        assert_eq!(metrics.word_count, 12);
+        let stats = analyzer.word_statistics(text);
+        assert!(stats.most_frequent.iter().all(|(_, count)| *count == 1));
+        assert_eq!(analyzer.analyze_stream(text.as_bytes(), 4).unwrap(), metrics);
+        assert!(TextAnalyzer::new().analyze_text(text).word_count > metrics.word_count);
+    }
+
+    #[test]
+    fn test_html_stripping() {
+        let analyzer = TextAnalyzer::new().with_html_stripping(true);
+        let metrics = analyzer.analyze_text("<p>Hello &amp; welcome</p>");
+        assert_eq!(metrics.word_count, 2);
+        assert_eq!(metrics.letter_count, "Hellowelcome".len());
+
+        let path = std::env::temp_dir().join("fog_test_html_stripping.html");
+        fs::write(&path, "<script>if (a < b) { run(); }</script><p>The cat sat.</p>").unwrap();
+        let from_file = analyzer.analyze_file(path.to_str().unwrap()).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(from_file, TextAnalyzer::new().analyze_text("The cat sat."));
+    }
+
+    #[test]
+    fn test_character_counts() {
+        let analyzer = TextAnalyzer::new();
+        let metrics = analyzer.analyze_text("Route 66, café!");
+        assert_eq!(metrics.letter_count, 9);
+        assert_eq!(metrics.alphanumeric_count, 11);
+        assert_eq!(metrics.total_char_count, 15);
+
+        // ARI counts the digits, Coleman-Liau only the letters
+        let words = metrics.word_count as f64;
+        let ari = 4.71 * (11.0 / words) + 0.5 * words - 21.43;
+        assert!((metrics.automated_readability_index - ari).abs() < 1e-9);
+        let coleman_liau = 0.0588 * (9.0 / words * 100.0) - 0.296 * (1.0 / words * 100.0) - 15.8;
+        assert!((metrics.coleman_liau_index - coleman_liau).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_degenerate_input_is_finite() {
+        let analyzer = TextAnalyzer::new().with_dale_chall_list(sample_easy_words());
+        for text in ["!!!???", "   \n\t  ", "...", "", "12345"] {
+            let metrics = analyzer.analyze_text(text);
+            assert_all_finite(&metrics);
+            assert_eq!(metrics.average_words_per_sentence, 0.0);
+            assert_eq!(metrics.average_syllables_per_word, 0.0);
+            assert!(analyzer.try_analyze_text(text).is_err());
+        }
+    }
+
+    #[test]
+    fn test_simple_sentence() {
+        let analyzer = TextAnalyzer::new();
+        let metrics = analyzer.analyze_text("The cat sat on the mat.");
+        assert_eq!(metrics.word_count, 6);
+        assert_eq!(metrics.sentence_count, 1);
+    }
+
+    #[test]
+    fn test_syllable_counting() {
+        let analyzer = TextAnalyzer::new();
+        assert_eq!(analyzer.count_syllables("cat"), 1);
+        assert_eq!(analyzer.count_syllables("water"), 2);
+        assert_eq!(analyzer.count_syllables("beautiful"), 3);
+        // Vowel runs count once and are not subtracted again
+        assert_eq!(analyzer.count_syllables("queue"), 1);
+        assert_eq!(analyzer.count_syllables("area"), 3);
+        assert_eq!(analyzer.count_syllables("media"), 3);
+        assert_eq!(analyzer.count_syllables("sea"), 1);
+        assert_eq!(analyzer.count_syllables("boat"), 1);
+    }
+
+    #[test]
+    fn test_affix_rules() {
+        let analyzer = TextAnalyzer::new().with_affix_rules(true);
+        assert_eq!(analyzer.count_syllables("nation"), 2);
+        assert_eq!(analyzer.count_syllables("creation"), 3);
+        assert_eq!(analyzer.count_syllables("variations"), 4);
+        assert_eq!(analyzer.count_syllables("happiness"), 3);
+        // The stem keeps its silent "e"
+        assert_eq!(analyzer.count_syllables("careful"), 2);
+        assert_eq!(analyzer.count_syllables("carefully"), 3);
+        assert_eq!(analyzer.count_syllables("hopelessness"), 3);
+        assert_eq!(analyzer.count_syllables("movements"), 2);
+        assert_eq!(analyzer.count_syllables("lovely"), 2);
+        assert_eq!(analyzer.count_syllables("mishandle"), 3);
+        assert_eq!(analyzer.count_syllables("unhappy"), 3);
+        // A suffix needs a stem with a vowel
+        assert_eq!(analyzer.count_syllables("bless"), 1);
+        assert_eq!(analyzer.count_syllables("fly"), 1);
+
+        let plain = TextAnalyzer::new();
+        assert_eq!(plain.count_syllables("careful"), 3);
+        assert_eq!(plain.count_syllables("creation"), 2);
+        let careful = analyzer.count_syllables_explained("careful");
+        assert_eq!(careful.source, SyllableSource::Compound);
+        assert_eq!(careful.parts.iter().map(|part| part.count).collect::<Vec<_>>(), [1, 1]);
+    }
+
+    #[test]
+    fn test_initialisms() {
+        let analyzer = TextAnalyzer::new();
+        // aitch-tee-em-el
+        assert_eq!(analyzer.count_syllables("HTML"), 4);
+        assert_eq!(analyzer.count_syllables("NASA"), 2);
+        assert_eq!(analyzer.count_syllables("WWW"), 9);
+        assert_eq!(analyzer.count_syllables("(BBC)"), 3);
+        assert_eq!(analyzer.count_syllables("HTML-like"), 5);
+        // Only all-caps tokens are spelled
+        assert_eq!(analyzer.count_syllables("hmm"), 1);
+        assert_eq!(analyzer.count_syllables("Mr"), 1);
+        assert_eq!(analyzer.count_syllables("I"), 1);
+    }
+
+    #[test]
+    fn test_contractions() {
+        let analyzer = TextAnalyzer::new();
+        assert_eq!(analyzer.analyze_text("I can't believe it's true.").word_count, 5);
+        assert_eq!(analyzer.analyze_text("We’ll see, won't we?").word_count, 4);
+        assert_eq!(analyzer.count_syllables("don't"), 1);
+        assert_eq!(analyzer.count_syllables("we'll"), 1);
+        assert_eq!(analyzer.count_syllables("they're"), 1);
+        // Quoting with apostrophes does not glue words together
+        assert_eq!(analyzer.analyze_text("She said 'hello' twice.").word_count, 4);
+    }
+
+    #[test]
+    fn test_numbers() {
+        let text = "In 2024 we sold 1,000 pies at 3.14 each. Prices rose 7 percent.";
+        let excluded = TextAnalyzer::new().analyze_text(text);
+        assert_eq!(excluded.sentence_count, 2);
+        assert_eq!(excluded.word_count, 9);
+
+        let analyzer = TextAnalyzer::new().with_number_handling(NumberHandling::Spoken);
+        let spoken = analyzer.analyze_text(text);
+        assert_eq!(spoken.sentence_count, 2);
+        assert_eq!(spoken.word_count, 13);
+        assert_eq!(spoken.syllable_count, excluded.syllable_count + 6 + 3 + 4 + 2);
+        assert_eq!(spoken.complex_word_count, excluded.complex_word_count);
+
+        // two thousand twenty-four
+        assert_eq!(spoken_number_syllables("2024"), 6);
+        // one thousand
+        assert_eq!(spoken_number_syllables("1,000"), 3);
+        // three point one four
+        assert_eq!(spoken_number_syllables("3.14"), 4);
+        // one hundred seventeen
+        assert_eq!(spoken_number_syllables("117"), 6);
+        assert_eq!(spoken_number_syllables("0"), 2);
+        assert_eq!(spoken_number_syllables("2,000,000"), 3);
+        assert_eq!(spoken_number_syllables("99999999999999999999"), 20);
+    }
+
+    #[test]
+    fn test_overlong_tokens() {
+        let analyzer = TextAnalyzer::new();
+        let blob: String = "abcdefghijklmnopqrstuvwxyz".chars().cycle().take(1 << 20).collect();
+        let metrics = analyzer.analyze_text(&blob);
+        assert_eq!(metrics.word_count, 1);
+        assert_eq!(metrics.syllable_count, 21);
+        assert_eq!(analyzer.analyze_stream(blob.as_bytes(), 4096).unwrap(), metrics);
+
+        assert!(analyzer.count_syllables("pneumonoultramicroscopicsilicovolcanoconiosis") > 10);
+        let strict = TextAnalyzer::new().with_max_word_length(5);
+        assert_eq!(strict.count_syllables("banana"), 1);
+        assert_eq!(strict.count_syllables("bana"), 2);
+    }
+
+    #[test]
+    fn test_hyphenated_compounds() {
+        let analyzer = TextAnalyzer::new();
+        let metrics = analyzer.analyze_text("My mother-in-law came.");
+        assert_eq!(metrics.word_count, 3);
+        assert_eq!(analyzer.count_syllables("mother-in-law"), 4);
+        assert_eq!(analyzer.count_syllables("state-of-the-art"), 4);
+        assert_eq!(analyzer.analyze_text("A well-known, state-of-the-art tool.").word_count, 4);
+        // Dashes used as punctuation still separate words
+        assert_eq!(analyzer.analyze_text("a — b").word_count, 2);
+        assert_eq!(analyzer.analyze_text("a - b").word_count, 2);
+        assert_eq!(analyzer.analyze_text("wait--what").word_count, 2);
+    }
+
+    #[test]
+    fn test_conditional_y() {
+        let analyzer = TextAnalyzer::new().with_conditional_y(true);
+        assert_eq!(analyzer.count_syllables("yellow"), 2);
+        assert_eq!(analyzer.count_syllables("happy"), 2);
+        assert_eq!(analyzer.count_syllables("year"), 1);
+        assert_eq!(analyzer.count_syllables("boy"), 1);
+        assert_eq!(analyzer.count_syllables("rhythm"), 1);
+        assert_eq!(analyzer.count_syllables("Yesterday"), 3);
+        assert_eq!(analyzer.count_syllables("player"), 2);
+
+        let always = TextAnalyzer::new();
+        assert_eq!(always.count_syllables("happy"), 2);
+        assert_eq!(always.count_syllables("player"), 1);
+    }
+
+    #[test]
+    fn test_consonant_le_syllables() {
+        let analyzer = TextAnalyzer::new();
+        assert_eq!(analyzer.count_syllables("table"), 2);
+        assert_eq!(analyzer.count_syllables("little"), 2);
+        assert_eq!(analyzer.count_syllables("bicycle"), 3);
+        assert_eq!(analyzer.count_syllables("simple"), 2);
+        // A vowel before "le" leaves the final e silent
+        assert_eq!(analyzer.count_syllables("whale"), 1);
+    }
+
+    #[test]
+    fn test_accented_vowels() {
+        let analyzer = TextAnalyzer::new();
+        assert_eq!(analyzer.count_syllables("café"), 2);
+        assert_eq!(analyzer.count_syllables("naïve"), 2);
+        assert_eq!(analyzer.count_syllables("résumé"), 3);
+        assert_eq!(analyzer.analyze_text("A naïve café.").word_count, 3);
+        // The plain English path is unchanged
+        assert_eq!(analyzer.count_syllables("cafe"), 1);
+        assert_eq!(analyzer.count_syllables("water"), 2);
+    }
+
+    #[test]
+    fn test_configured_vowels() {
+        let ascii = TextAnalyzer::new().with_vowels("aeiouy");
+        let spanish = TextAnalyzer::new().with_vowels("aeiouáéíóúü");
+        let german = TextAnalyzer::new().with_vowels("aeiouyäöü");
+        assert_eq!(ascii.count_syllables("música"), 2);
+        assert_eq!(spanish.count_syllables("música"), 3);
+        assert_eq!(german.count_syllables("über"), 2);
+        assert_eq!(spanish.analyze_text("La música es buena.").word_count, 4);
+    }
+
+    #[test]
+    fn test_builder_complex_word_rules() {
+        let text = "Water flows gently. Computers are amusing.";
+        let default = TextAnalyzer::new().analyze_text(text);
+        let built = TextAnalyzer::builder().build().analyze_text(text);
+        assert_eq!(built, default);
+        // computers
+        assert_eq!(default.complex_word_count, 1);
+
+        let lower = TextAnalyzer::builder().complex_syllable_threshold(2).build();
+        // water, gently, computers, and amusing as "amus" has 2 syllables
+        assert_eq!(lower.analyze_text(text).complex_word_count, 4);
+        // SMOG counts polysyllables whatever the threshold
+        let long_text = "Extraordinary complications arise. The cat sat. ".repeat(15);
+        let lowered = lower.analyze_text(&long_text);
+        let canonical = TextAnalyzer::new().analyze_text(&long_text);
+        assert_eq!(lowered.polysyllable_count, canonical.polysyllable_count);
+        assert_eq!(lowered.smog_index, canonical.smog_index);
+        assert!(lowered.gunning_fog_index > canonical.gunning_fog_index);
+
+        let inflected = TextAnalyzer::builder().exclude_inflections(false).build();
+        let metrics = inflected.analyze_text(text);
+        // computers, amusing
+        assert_eq!(metrics.complex_word_count, 2);
+        assert!(metrics.gunning_fog_index > default.gunning_fog_index);
+    }
+
+    #[test]
+    fn test_inflected_complex_words() {
+        let analyzer = TextAnalyzer::new();
+        let complex = |word: &str| analyzer.is_complex_word(word, analyzer.count_syllables(word));
+        // The stem alone has 3 syllables
+        assert!(complex("interested"));
+        assert!(complex("decorated"));
+        // Full stems keep their inflections complex
+        assert!(complex("proceeding"));
+        assert!(complex("processes"));
+        assert!(complex("collecting"));
+        // The ending stands in for a silent "e" of a 2-syllable word
+        assert!(!complex("amusing"));
+        assert!(!complex("decided"));
+        let inflected = TextAnalyzer::builder().exclude_inflections(false).build();
+        assert!(inflected.is_complex_word("amusing", inflected.count_syllables("amusing")));
+    }
+
+    #[test]
+    fn test_smog_requires_thirty_sentences() {
+        let analyzer = TextAnalyzer::new();
+        let short = analyzer.analyze_text("Extraordinary complications arise. The cat sat.");
+        assert_eq!(short.smog_index, None);
+        assert!(short.csv_values()[TextMetrics::CSV_COLUMNS.iter().position(|c| *c == "smog_index").unwrap()].is_empty());
+
+        let long = analyzer.analyze_text(&"Extraordinary complications arise. The cat sat. ".repeat(15));
+        assert_eq!(long.sentence_count, 30);
+        let expected = 1.0430 * f64::sqrt(30.0) + 3.1291;
+        assert!((long.smog_index.unwrap() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_smog_sampling() {
+        let approximate = TextAnalyzer::new();
+        let sampled = TextAnalyzer::new().with_smog_mode(SmogMode::Sampled);
+
+        // With polysyllables spread evenly the approximation is exact
+        let even = "Extraordinary complications arise. The cat sat. ".repeat(30);
+        let (a, b) = (approximate.analyze_text(&even), sampled.analyze_text(&even));
+        assert!((a.smog_index.unwrap() - b.smog_index.unwrap()).abs() < 1e-9);
+
+        // 60 sentences whose 60 polysyllables all sit outside the sampled ones
+        let plain = "The cat sat. ".repeat(10);
+        let hard = "Extraordinary complications. ".repeat(15);
+        let uneven = [plain.as_str(), &hard, &plain, &hard, &plain].concat();
+        let expected_approximate = 1.0430 * f64::sqrt(60.0 * 30.0 / 60.0) + 3.1291;
+        assert_eq!(approximate.analyze_text(&uneven).sentence_count, 60);
+        assert!((approximate.analyze_text(&uneven).smog_index.unwrap() - expected_approximate).abs() < 1e-9);
+        assert_eq!(sampled.analyze_text(&uneven).smog_index, Some(3.1291));
+        assert_eq!(sampled.analyze_stream(uneven.as_bytes(), 16).unwrap(), sampled.analyze_text(&uneven));
+
+        assert_eq!(sampled.analyze_text("The cat sat.").smog_index, None);
+    }
+
+    #[test]
+    fn test_reading_level_label() {
+        let analyzer = TextAnalyzer::new();
+        let mut metrics = analyzer.analyze_text("The cat sat on the mat.");
+        assert_eq!(metrics.reading_level_label(), "Very Easy (5th grade)");
+        for (score, label) in [
+            (80.0, "Easy (6th grade)"),
+            (65.0, "Standard (8th–9th grade)"),
+            (50.0, "Fairly Difficult (10th–12th grade)"),
+            (29.9, "Very Difficult (college graduate)"),
+            (-12.0, "Very Difficult (college graduate)"),
+        ] {
+            metrics.flesch_reading_ease = score;
+            assert_eq!(metrics.reading_level_label(), label);
+        }
+    }
+
+    #[test]
+    fn test_coleman_liau() {
+        let analyzer = TextAnalyzer::new();
+        // 17 letters, 6 words, 1 sentence
+        let metrics = analyzer.analyze_text("The cat sat on the mat.");
+        let expected = 0.0588 * (17.0 / 6.0 * 100.0) - 0.296 * (1.0 / 6.0 * 100.0) - 15.8;
+        assert!((metrics.coleman_liau_index - expected).abs() < 1e-9);
+        assert_eq!(analyzer.analyze_text("").coleman_liau_index, 0.0);
+    }
+
+    #[test]
+    fn test_automated_readability_index() {
+        let analyzer = TextAnalyzer::new();
+        let metrics = analyzer.analyze_text("The cat sat on the mat.");
+        let expected = 4.71 * (17.0 / 6.0) + 0.5 * 6.0 - 21.43;
+        assert!((metrics.automated_readability_index - expected).abs() < 1e-9);
+        assert_eq!(analyzer.analyze_text("").automated_readability_index, 0.0);
+    }
+
+    fn sample_easy_words() -> HashSet<String> {
+        ["the", "cat", "sat", "on", "mat"].iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_dale_chall_familiar_text() {
+        let analyzer = TextAnalyzer::new().with_dale_chall_list(sample_easy_words());
+        let metrics = analyzer.analyze_text("The cat sat on the mat.");
+        assert_eq!(metrics.difficult_word_count, 0);
+        assert!((metrics.dale_chall_score - 0.0496 * 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dale_chall_difficult_words() {
+        let analyzer = TextAnalyzer::new().with_dale_chall_list(sample_easy_words());
+        let text = "The cat sat on the extraordinary mat.";
+        assert_eq!(analyzer.analyze_text(text).difficult_word_count, 1);
+        // 1 of 7 words is difficult, which is above the 5% adjustment threshold
+        let expected = 0.1579 * (100.0 / 7.0) + 0.0496 * 7.0 + 3.6365;
+        assert!((analyzer.calculate_dale_chall(text) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dale_chall_grade_range() {
+        let mut metrics = TextAnalyzer::new().analyze_text("The cat sat.");
+        let mut band = |score: f64| {
+            metrics.dale_chall_score = score;
+            metrics.dale_chall_grade_range()
+        };
+        assert_eq!(band(0.0), "grade 4 and below");
+        assert_eq!(band(4.9), "grade 4 and below");
+        assert_eq!(band(5.0), "grades 5–6");
+        assert_eq!(band(5.9), "grades 5–6");
+        assert_eq!(band(6.0), "grades 7–8");
+        assert_eq!(band(7.0), "grades 9–10");
+        assert_eq!(band(8.99), "grades 11–12");
+        assert_eq!(band(9.0), "grades 13–15 (college)");
+        assert_eq!(band(10.0), "grades 16 and above (college graduate)");
+    }
+
+    #[test]
+    fn test_spache() {
+        let analyzer = TextAnalyzer::new().with_spache_list(sample_easy_words());
+        let text = "The cat sat on the extraordinary mat.";
+        let metrics = analyzer.analyze_text(text);
+        assert_eq!(metrics.unfamiliar_word_count, 1);
+        let expected = 0.141 * 7.0 + 0.086 * (100.0 / 7.0) + 0.839;
+        assert!((analyzer.calculate_spache(text) - expected).abs() < 1e-9);
+        // The Spache list is independent of the Dale-Chall list
+        assert_eq!(metrics.difficult_word_count, 0);
+        assert_eq!(TextAnalyzer::new().calculate_spache(text), 0.0);
+    }
+
+    #[test]
+    fn test_list_stemming() {
+        let words: HashSet<String> = ["jump", "hope", "run", "carry", "dog"].iter().map(|w| w.to_string()).collect();
+        let text = "Dogs jumped. He hoped. Running carries risk.";
+        let exact = TextAnalyzer::new().with_dale_chall_list(words.clone());
+        assert_eq!(exact.analyze_text(text).difficult_word_count, 7);
+        let stemmed = TextAnalyzer::new()
+            .with_dale_chall_list(words.clone())
+            .with_spache_list(words)
+            .with_list_stemming(true);
+        let metrics = stemmed.analyze_text(text);
+        // Only "He" and "risk" are missing from the list
+        assert_eq!(metrics.difficult_word_count, 2);
+        assert_eq!(metrics.unfamiliar_word_count, 2);
+        assert_eq!(inflection_stems("played"), ["play", "playe"]);
+    }
+
+    #[test]
+    fn test_hardest_sentences() {
+        let analyzer = TextAnalyzer::new();
+        let text = "The cat sat. Extraordinary complications arise. Dogs run fast. \
+                    We walked to the park and sat down. Hot sun.";
+        let hardest = analyzer.hardest_sentences(text, 3);
+        let texts: Vec<&str> = hardest.iter().map(|(_, sentence)| sentence.as_str()).collect();
+        // One-syllable sentences tie on syllables per word, so more words means a higher grade
+        assert_eq!(texts, ["Extraordinary complications arise.", "We walked to the park and sat down.", "The cat sat."]);
+        assert!(hardest.windows(2).all(|pair| pair[0].0 >= pair[1].0));
+        // "The cat sat." and "Dogs run fast." have the same grade
+        assert_eq!(hardest[2].0, analyzer.hardest_sentences("Dogs run fast.", 1)[0].0);
+        assert_eq!(analyzer.hardest_sentences(text, 10).len(), 5);
+        assert!(analyzer.hardest_sentences(text, 0).is_empty());
+
+        let scored = analyzer.score_sentences(text);
+        assert_eq!(scored.len(), 5);
+        let (sentence, metrics) = &scored[1];
+        assert_eq!(sentence, "Extraordinary complications arise.");
+        assert_eq!(*metrics, analyzer.analyze_text(sentence));
+        // Markdown is stripped once, before the text is split
+        let markdown = TextAnalyzer::new().with_markdown_stripping(true);
+        let scored = markdown.score_sentences("Read **this** now. Then stop.");
+        assert_eq!(scored[0].0, "Read this now.");
+        assert_eq!(scored[0].1.word_count, 3);
+    }
+
+    #[test]
+    fn test_count_syllables_explained() {
+        let analyzer = TextAnalyzer::new();
+        let make = analyzer.count_syllables_explained("make");
+        assert_eq!((make.vowel_groups, make.silent_e, make.clamped), (2, true, false));
+        assert_eq!(make.count, 1);
+        assert_eq!(make.source, SyllableSource::Rules);
+
+        let table = analyzer.count_syllables_explained("table");
+        assert!(!table.silent_e);
+        assert_eq!(table.count, 2);
+
+        let hmm = analyzer.count_syllables_explained("hmm");
+        assert_eq!((hmm.vowel_groups, hmm.clamped, hmm.count), (0, true, 1));
+
+        let compound = analyzer.count_syllables_explained("well-made");
+        assert_eq!(compound.source, SyllableSource::Compound);
+        assert_eq!(compound.parts.iter().map(|part| part.count).collect::<Vec<_>>(), [1, 1]);
+        assert_eq!(compound.count, 2);
+
+        let initialism = analyzer.count_syllables_explained("HTML");
+        assert_eq!((initialism.source, initialism.count), (SyllableSource::Spelled, 4));
+
+        let token = analyzer.count_syllables_explained(&"x".repeat(100));
+        assert_eq!(token.source, SyllableSource::LengthEstimate);
+        assert_eq!(analyzer.count_syllables_explained("").count, 0);
+    }
+
+    #[test]
+    fn test_passive_voice_ratio() {
+        let analyzer = TextAnalyzer::new();
+        assert_eq!(analyzer.passive_voice_ratio("The ball was thrown by John."), 1.0);
+        assert_eq!(analyzer.passive_voice_ratio("John threw the ball."), 0.0);
+        assert_eq!(analyzer.passive_voice_ratio("The report is being carefully reviewed."), 1.0);
+        assert_eq!(analyzer.passive_voice_ratio("The door was not locked."), 1.0);
+        let text = "Mistakes were made. She was happy. The cake was eaten. We left early.";
+        assert_eq!(analyzer.passive_voice_ratio(text), 0.5);
+        assert_eq!(analyzer.passive_voice_ratio(""), 0.0);
+    }
+
+    #[test]
+    fn test_sentence_length_stats() {
+        let analyzer = TextAnalyzer::new();
+        // 2, 4 and 6 words
+        let stats = analyzer.sentence_length_stats("Dogs bark. The cat sat down. We walked to the park today.");
+        assert_eq!(stats.min, 2);
+        assert_eq!(stats.max, 6);
+        assert!((stats.mean - 4.0).abs() < 1e-9);
+        assert!((stats.std_dev - (8.0f64 / 3.0).sqrt()).abs() < 1e-9);
+        assert_eq!(analyzer.sentence_length_stats("Same size. Same size.").std_dev, 0.0);
+        assert_eq!(analyzer.sentence_length_stats(""), SentenceLengthStats::default());
+    }
+
+    #[test]
+    fn test_word_statistics_surface_forms() {
+        let analyzer = TextAnalyzer::new().with_surface_forms(true);
+        let stats = analyzer.word_statistics("The the THE");
+        assert_eq!(stats.most_frequent, [("the".to_string(), 3)]);
+        assert_eq!(stats.unique_words, 1);
+        let stats = analyzer.word_statistics("Rust is fun. Rust is fast. RUST!");
+        assert_eq!(stats.most_frequent[0], ("Rust".to_string(), 3));
+        let stats = analyzer.word_statistics("NASA and Nasa NASA nasa");
+        assert_eq!(stats.most_frequent[0], ("NASA".to_string(), 4));
+    }
+
+    #[test]
+    fn test_lix_index() {
+        let analyzer = TextAnalyzer::new();
+        let text = "Readability formulas estimate difficulty. Short words help. \
+                    Complicated vocabulary increases scores.";
+        let metrics = analyzer.analyze_text(text);
+        // readability, formulas, estimate, difficulty, complicated, vocabulary, increases
+        assert_eq!(metrics.word_count, 11);
+        assert_eq!(metrics.sentence_count, 3);
+        assert_eq!(metrics.long_word_count, 7);
+        let expected = 11.0 / 3.0 + 7.0 * 100.0 / 11.0;
+        assert!((metrics.lix_index - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fry() {
+        let analyzer = TextAnalyzer::new();
+        // 9 words, 10 syllables (happy has two), 2 sentences
+        let (syllables, sentences) = analyzer.fry_coordinates("The cat sat on the mat. It was happy.");
+        assert!((syllables - 10.0 / 9.0 * 100.0).abs() < 1e-9);
+        assert!((sentences - 2.0 / 9.0 * 100.0).abs() < 1e-9);
+        assert_eq!(analyzer.fry_grade("The cat sat on the mat. It was happy."), 1);
+        assert_eq!(analyzer.fry_coordinates(""), (0.0, 0.0));
+
+        let hard = "Extraordinary complications arise from miscellaneous circumstances \
+                    involving international organizations and governmental institutions.";
+        assert_eq!(analyzer.fry_grade(hard), 13);
+    }
+
+    #[test]
+    fn test_shared_patterns() {
+        let analyzer = TextAnalyzer::new();
+        let clone = analyzer.clone();
+        assert!(Arc::ptr_eq(&analyzer.patterns, &clone.patterns));
+        // New analyzers reuse the default patterns instead of compiling them
+        assert!(Arc::ptr_eq(&analyzer.patterns, &TextAnalyzer::new().patterns));
+
+        let semicolons = clone.clone().with_sentence_terminators(".;");
+        assert!(!Arc::ptr_eq(&semicolons.patterns, &analyzer.patterns));
+        assert!(Arc::ptr_eq(&semicolons.clone().patterns, &semicolons.patterns));
+        assert_eq!(semicolons.analyze_text("One; two.").sentence_count, 2);
+        assert_eq!(clone.analyze_text("One; two.").sentence_count, 1);
+    }
+
+    #[test]
+    fn test_citation_stripping() {
+        let analyzer = TextAnalyzer::new().with_citation_stripping(CitationStripping::Markers);
+        let metrics = analyzer.analyze_text("as shown [12].");
+        assert_eq!((metrics.sentence_count, metrics.word_count), (1, 2));
+        assert_eq!(metrics, TextAnalyzer::new().analyze_text("as shown."));
+
+        let text = "Sleep helps (Smith et al., 2020). Naps help too.";
+        assert_eq!(analyzer.analyze_text(text).word_count, 8);
+        let all = TextAnalyzer::new().with_citation_stripping(CitationStripping::All);
+        assert_eq!(all.analyze_text(text), TextAnalyzer::new().analyze_text("Sleep helps. Naps help too."));
+        assert_eq!(all.analyze_stream(text.as_bytes(), 4).unwrap(), all.analyze_text(text));
+    }
+
+    #[test]
+    fn test_line_rejoining() {
+        let text = "Extraordinary inter-\nnational complica-\ntions arose. The self-\nmade man left.";
+        let plain = TextAnalyzer::new().analyze_text(text);
+        let analyzer = TextAnalyzer::new().with_line_rejoining(true);
+        let metrics = analyzer.analyze_text(text);
+        assert_eq!(plain.word_count, 11);
+        // "inter-\nnational" and "complica-\ntions" are one word each, and
+        // "self-\nmade" one word that keeps its hyphen
+        assert_eq!(metrics.word_count, 8);
+        assert_eq!(metrics, analyzer.analyze_text("Extraordinary international complications arose. The self-made man left."));
+        assert_eq!(analyzer.analyze_stream(text.as_bytes(), 4).unwrap(), metrics);
+    }
+
+    #[test]
+    fn test_repeated_words() {
+        let analyzer = TextAnalyzer::new();
+        assert_eq!(analyzer.repeated_words("I saw the the dog"), [(10, "the".to_string())]);
+        let repeated = analyzer.repeated_words("The, the end; it is IS done.");
+        assert_eq!(repeated, [(5, "the".to_string()), (20, "IS".to_string())]);
+        // Three in a row are two repetitions
+        assert_eq!(analyzer.repeated_words("no no no").len(), 2);
+        assert!(analyzer.repeated_words("Bye. Bye.").is_empty());
+        assert!(analyzer.repeated_words("I saw the dog").is_empty());
+    }
+
+    #[test]
+    fn test_complexity_exclusions() {
+        let text = "The Motorola phone rang. We all answered it.";
+        let plain = TextAnalyzer::new().analyze_text(text);
+        assert_eq!(plain.complex_word_count, 1);
+
+        let analyzer = TextAnalyzer::new().with_complexity_exclusions(HashSet::from(["motorola".to_string()]));
+        let excluded = analyzer.analyze_text(text);
+        assert_eq!(excluded.complex_word_count, 0);
+        assert!(excluded.gunning_fog_index < plain.gunning_fog_index);
+        // Still four syllables for the other formulas
+        assert_eq!(excluded.syllable_count, plain.syllable_count);
+        assert_eq!(excluded.polysyllable_count, plain.polysyllable_count);
+    }
+
+    #[test]
+    fn test_raygor() {
+        let analyzer = TextAnalyzer::new();
+        // 10 words in 2 sentences, two of them ("matted", "gardens") long
+        let text = "The cat sat on matted rugs. It sat in gardens.";
+        let (sentences, long_words) = analyzer.raygor_coordinates(text);
+        assert!((sentences - 20.0).abs() < 1e-9);
+        assert!((long_words - 20.0).abs() < 1e-9);
+        assert_eq!(analyzer.raygor_coordinates(""), (0.0, 0.0));
+        assert_eq!(analyzer.clone().with_cjk_mode(true).raygor_coordinates(text), (0.0, 0.0));
+        assert_eq!(analyzer.raygor_grade("The cat sat on the mat. It was happy. We ran."), 3);
+
+        let hard = "Extraordinary complications arise from miscellaneous circumstances \
+                    involving international organizations and governmental institutions.";
+        assert_eq!(analyzer.raygor_grade(hard), 14);
+    }
+
+    #[test]
+    fn test_consensus_grade() {
+        let analyzer = TextAnalyzer::new();
+        let short = analyzer.analyze_text("Extraordinary complications arise. The miscellaneous circumstances persist.");
+        // SMOG is not valid under 30 sentences and is left out
+        let mean = (short.flesch_kincaid_grade
+            + short.gunning_fog_index
+            + short.coleman_liau_index
+            + short.automated_readability_index)
+            / 4.0;
+        assert_eq!(short.consensus_grade(), mean.round());
+
+        let long = analyzer.analyze_text(&"Extraordinary complications arise. The cat sat. ".repeat(15));
+        let smog = long.smog_index.unwrap();
+        let mean = (long.flesch_kincaid_grade
+            + long.gunning_fog_index
+            + long.coleman_liau_index
+            + long.automated_readability_index
+            + smog)
+            / 5.0;
+        assert_eq!(long.consensus_grade(), mean.round());
+        assert_eq!(analyzer.analyze_text("Go. Run. Sit.").consensus_grade(), 0.0);
+    }
+
+    #[test]
+    fn test_rix_index() {
+        let analyzer = TextAnalyzer::new();
+        // Long words: extraordinary, complications, miscellaneous, circumstances, persist
+        let text = "Extraordinary complications arise. The miscellaneous circumstances persist.";
+        let metrics = analyzer.analyze_text(text);
+        assert_eq!(metrics.long_word_count, 5);
+        assert_eq!(metrics.rix_index, 2.5);
+        // LIX is the same long words per 100 words plus words per sentence
+        assert_eq!(metrics.lix_index, 3.5 + 5.0 * 100.0 / 7.0);
+        assert_eq!(analyzer.calculate_rix(metrics.long_word_count, metrics.sentence_count), 2.5);
+        assert_eq!(analyzer.calculate_rix(5, 0), 0.0);
+    }
+
+    #[test]
+    fn test_linsear_write() {
+        let analyzer = TextAnalyzer::new();
+        // Six easy words in one sentence: (6 - 2) / 2
+        assert_eq!(analyzer.analyze_text("The cat sat on the mat.").linsear_write, 2.0);
+        // Provisional score above 20 is halved without the subtraction
+        let counts = |words, hard_words| RawCounts {
+            word_count: words,
+            polysyllable_count: hard_words,
+            sentence_count: 1,
+            ..RawCounts::default()
+        };
+        assert_eq!(LinsearWrite.score(&counts(15, 5)), 12.5);
+        assert_eq!(LinsearWrite.score(&counts(0, 0)), 0.0);
+    }
+
+    #[test]
+    fn test_cjk_mode() {
+        let text = "私は毎朝コーヒーを飲みます。今日は雨です。";
+        assert!(is_mostly_cjk(text));
+        assert!(!is_mostly_cjk("The cat sat on the mat."));
+        // Without CJK mode each unspaced run is one word and "。" ends nothing
+        let plain = TextAnalyzer::new().analyze_text(text);
+        assert_eq!((plain.word_count, plain.sentence_count), (2, 1));
+
+        let analyzer = TextAnalyzer::new().with_cjk_mode(true);
+        let metrics = analyzer.analyze_text(text);
+        assert_eq!(metrics.word_count, 19);
+        assert_eq!(metrics.sentence_count, 2);
+        assert_eq!(metrics.letter_count, 19);
+        assert_eq!(metrics.syllable_count, 0);
+        assert_eq!((metrics.flesch_reading_ease, metrics.gunning_fog_index), (0.0, 0.0));
+        assert_eq!(metrics.rix_index, 0.0);
+        assert!(metrics.coleman_liau_index != 0.0);
+        // Latin words inside a run stay whole
+        assert_eq!(analyzer.analyze_text("Rust言語が好き").word_count, 6);
+        let partial = analyzer.analyze_text_with(text, MetricSet::ALL);
+        assert_eq!(partial.flesch_kincaid_grade, None);
+        assert_eq!(partial.counts.word_count, 19);
+    }
+
+    #[test]
+    fn test_sentence_terminators() {
+        let text = "¿Dónde está la biblioteca? ¡Qué día tan bonito! Vamos ya; llegamos tarde.";
+        let spanish = TextAnalyzer::new().with_vowels("aeiouáéíóúü");
+        let metrics = spanish.analyze_text(text);
+        assert_eq!(metrics.sentence_count, 3);
+        assert_eq!(metrics.word_count, 12);
+        assert_eq!(spanish.split_sentences(text)[1], "¡Qué día tan bonito!");
+
+        let semicolons = spanish.with_sentence_terminators(".!?;");
+        assert_eq!(semicolons.analyze_text(text).sentence_count, 4);
+        assert_eq!(semicolons.analyze_text("Uno;; dos").sentence_count, 1);
+
+        let japanese = TextAnalyzer::new().with_sentence_terminators("。！？");
+        assert_eq!(japanese.analyze_text("猫が座った。犬も！ The end.").sentence_count, 2);
+    }
+
+    #[test]
+    fn test_analyze_paragraphs() {
+        let analyzer = TextAnalyzer::new();
+        let text = "\r\nThe cat sat.\r\nIt was happy.\r\n\r\n  \r\n\r\nExtraordinary complications arise.\n\n\n";
+        let paragraphs = analyzer.analyze_paragraphs(text);
+        assert_eq!(paragraphs.len(), 2);
+        assert_eq!(paragraphs[0], analyzer.analyze_text("The cat sat.\nIt was happy."));
+        assert_eq!(paragraphs[1], analyzer.analyze_text("Extraordinary complications arise."));
+        assert!(paragraphs[1].flesch_kincaid_grade > paragraphs[0].flesch_kincaid_grade);
+        assert!(analyzer.analyze_paragraphs(" \n\n").is_empty());
+
+        // The blank line inside the fence doesn't split the code block
+        let markdown = "Intro.\n\n```\ncode\n\nmore code\n```\n\nEnd.";
+        let paragraphs = analyzer.analyze_paragraphs(markdown);
+        assert_eq!(paragraphs.len(), 3);
+        assert_eq!(paragraphs[1].word_count, 3);
+        let stripped = TextAnalyzer::new().with_code_exclusion(true).analyze_paragraphs(markdown);
+        let words: Vec<usize> = stripped.iter().map(|paragraph| paragraph.word_count).collect();
+        assert_eq!(words, [1, 0, 1]);
+        assert_eq!(analyzer.analyze_paragraphs("Intro.\n\n```\ncode\n\nmore").len(), 2);
+    }
+
+    #[test]
+    fn test_analyze_sentences() {
+        let analyzer = TextAnalyzer::new();
+        let sentences = analyzer.analyze_sentences("The cat sat. Extraordinary complications arise! Done");
+        assert_eq!(sentences.len(), 3);
+        assert_eq!(sentences[0].text, "The cat sat.");
+        assert_eq!(sentences[0].word_count, 3);
+        assert_eq!(sentences[0].syllable_count, 3);
+        assert_eq!(sentences[1].text, "Extraordinary complications arise!");
+        assert!(sentences[1].flesch_reading_ease < sentences[0].flesch_reading_ease);
+        assert_eq!(sentences[2].text, "Done");
+    }
+
+    #[test]
+    fn test_sentence_positions() {
+        let analyzer = TextAnalyzer::new();
+        let text = "  Café au lait.\nWe ordered\n   one more.";
+        let sentences = analyzer.analyze_sentences(text);
+        assert_eq!(sentences.len(), 2);
+        assert_eq!((sentences[0].offset, sentences[0].line, sentences[0].column), (2, 1, 3));
+        // The offset is in bytes ("é" takes two), the column in characters
+        assert_eq!((sentences[1].offset, sentences[1].line, sentences[1].column), (17, 2, 1));
+        assert!(text[sentences[1].offset..].starts_with(&sentences[1].text));
+
+        let sentences = analyzer.analyze_sentences("One.   Two.");
+        assert_eq!((sentences[1].offset, sentences[1].line, sentences[1].column), (7, 1, 8));
+    }
+
+    #[test]
+    fn test_word_statistics() {
+        let analyzer = TextAnalyzer::new();
+        let stats = analyzer.word_statistics("The cat saw the dog. The dog saw an elephant.");
+        assert_eq!(stats.most_frequent[0], ("the".to_string(), 3));
+        assert_eq!(stats.most_frequent[1], ("dog".to_string(), 2));
+        assert_eq!(stats.most_frequent[2], ("saw".to_string(), 2));
+        assert_eq!(stats.longest_word.as_deref(), Some("elephant"));
+        assert_eq!(stats.unique_words, 6);
+        assert!((stats.type_token_ratio - 6.0 / 10.0).abs() < 1e-9);
+
+        let empty = analyzer.word_statistics("");
+        assert!(empty.most_frequent.is_empty());
+        assert_eq!(empty.longest_word, None);
+        assert_eq!(empty.type_token_ratio, 0.0);
+    }
+
+    #[test]
+    fn test_abbreviations_do_not_split_sentences() {
+        let analyzer = TextAnalyzer::new();
+        assert_eq!(analyzer.analyze_text("Dr. Smith went home.").sentence_count, 1);
+        assert_eq!(analyzer.analyze_text("The U.S.A. is large. It has many states.").sentence_count, 2);
+        assert_eq!(analyzer.analyze_text("Use a short word, e.g. cat, instead.").sentence_count, 1);
+        assert_eq!(analyzer.analyze_text("The answer is no. We left.").sentence_count, 2);
+        assert_eq!(analyzer.analyze_text("See fig. 3 and no. 12 for details.").sentence_count, 1);
+    }
+
+    #[test]
+    fn test_decimal_numbers_do_not_split_sentences() {
+        let analyzer = TextAnalyzer::new();
+        assert_eq!(analyzer.analyze_text("Pi is roughly 3.14 in value.").sentence_count, 1);
+        let sentences = analyzer.analyze_sentences("It costs 2.50 dollars. That is cheap.");
+        assert_eq!(sentences[0].text, "It costs 2.50 dollars.");
+    }
+
+    #[test]
+    fn test_addresses_do_not_split_sentences() {
+        let analyzer = TextAnalyzer::new();
+        assert_eq!(analyzer.analyze_text("Visit www.example.com today.").sentence_count, 1);
+        let text = "Search https://example.com/find?q=fog&page=2 or mail help.desk@example.co.uk now. Thanks!";
+        assert_eq!(analyzer.analyze_text(text).sentence_count, 2);
+        // A period straight after an address still ends the sentence
+        let sentences = analyzer.analyze_sentences("See www.example.com/docs. It helps.");
+        assert_eq!(sentences[0].text, "See www.example.com/docs.");
+        assert_eq!(sentences.len(), 2);
+    }
+
+    #[test]
+    fn test_streaming_matches_in_memory() {
+        let analyzer = TextAnalyzer::new();
+        let text = "Dr. Smith paused... then left!  Extraordinary complications arise.\n\
+                    Pi is 3.14 and the café is naïve. She said \"go.\" Short words help";
+        let expected = analyzer.analyze_text(text);
+        // Tiny chunks force words, abbreviations and multibyte chars across boundaries
+        for chunk_size in [1, 3, 7, 16] {
+            let streamed = analyzer.analyze_stream(text.as_bytes(), chunk_size).unwrap();
+            assert_eq!(streamed, expected);
+        }
+    }
+
+    #[test]
+    fn test_analyze_file() {
+        let path = std::env::temp_dir().join("fog_test_analyze_file.txt");
+        std::fs::write(&path, "The cat sat on the mat. It was happy.").unwrap();
+        let analyzer = TextAnalyzer::new();
+        let metrics = analyzer.analyze_file(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(metrics, analyzer.analyze_text("The cat sat on the mat. It was happy."));
+        assert!(matches!(analyzer.analyze_file("does/not/exist.txt"), Err(AnalyzerError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_analyze_reader() {
+        let analyzer = TextAnalyzer::new();
+        let expected = analyzer.analyze_text("The cat sat on the mat. It was happy.");
+        let cursor = io::Cursor::new("The cat sat on the mat. It was happy.");
+        assert_eq!(analyzer.analyze_reader(cursor).unwrap(), expected);
+        // A word split across two chained readers is still one word
+        let chained = "The cat sat on the m".as_bytes().chain("at. It was happy.".as_bytes());
+        assert_eq!(analyzer.analyze_reader(chained).unwrap(), expected);
+        let invalid: &[u8] = &[b'T', b'h', 0xff, b'.'];
+        let error = analyzer.analyze_reader(invalid).unwrap_err();
+        assert!(matches!(error, AnalyzerError::Encoding(EncodingError::InvalidUtf8 { offset: 2 })));
+        assert!(matches!(analyzer.analyze_reader(io::Cursor::new(" ... ")), Err(AnalyzerError::EmptyInput)));
+        let failing = ErrorReader.chain(io::Cursor::new("unread"));
+        assert!(matches!(analyzer.analyze_reader(failing), Err(AnalyzerError::Io(_))));
+    }
+
+    struct ErrorReader;
+
+    impl Read for ErrorReader {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("disconnected"))
+        }
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_gzip_files() {
+        let analyzer = TextAnalyzer::new();
+        let expected = analyzer.analyze_file(&fixture("sample.txt")).unwrap();
+        assert_eq!(analyzer.analyze_file(&fixture("sample.txt.gz")).unwrap(), expected);
+        let text = analyzer.read_text(&fixture("sample.txt.gz")).unwrap();
+        assert_eq!(text, analyzer.read_text(&fixture("sample.txt")).unwrap());
+        assert_eq!(expected.sentence_count, 4);
+    }
+
+    #[test]
+    fn test_analyze_directory() {
+        let dir = std::env::temp_dir().join("fog_test_analyze_directory");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("a.txt"), "The cat sat on the mat.").unwrap();
+        fs::write(dir.join("b.txt"), [0xff, 0xfe, 0x00]).unwrap();
+        fs::write(dir.join("notes.md"), "Not analyzed.").unwrap();
+        fs::write(dir.join("nested").join("c.txt"), "Dogs run. Cats nap.").unwrap();
+
+        let analyzer = TextAnalyzer::new();
+        let flat = analyzer.analyze_directory(dir.to_str().unwrap(), false).unwrap();
+        let recursive = analyzer.analyze_directory(dir.to_str().unwrap(), true).unwrap();
+        let mut calls = Vec::new();
+        let with_progress = analyzer
+            .analyze_directory_with_progress(dir.to_str().unwrap(), true, |index, total, path| {
+                calls.push((index, total, path.to_string()))
+            })
+            .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(with_progress.len(), recursive.len());
+        assert_eq!(calls.len(), 3);
+        assert!(calls.iter().enumerate().all(|(i, (index, total, _))| *index == i && *total == 3));
+        assert!(calls[2].2.ends_with("c.txt"));
+
+        // The invalid UTF-8 file is reported without stopping the batch
+        assert_eq!(flat.len(), 2);
+        assert!(flat[0].0.ends_with("a.txt"));
+        assert_eq!(flat[0].1.as_ref().unwrap().word_count, 6);
+        assert!(flat[1].1.is_err());
+
+        assert_eq!(recursive.len(), 3);
+        assert!(recursive[2].0.ends_with("c.txt"));
+        assert_eq!(recursive[2].1.as_ref().unwrap().sentence_count, 2);
+    }
+
+    #[test]
+    fn test_analyze_many() {
+        let analyzer = TextAnalyzer::new();
+        let docs = ["The cat sat.", "", "Extraordinary complications arise!"];
+        let results = analyzer.analyze_many(&docs);
+        assert_eq!(results.len(), 3);
+        for (doc, metrics) in docs.iter().zip(&results) {
+            assert_eq!(*metrics, analyzer.analyze_text(doc));
+        }
+        #[cfg(feature = "rayon")]
+        assert_eq!(analyzer.par_analyze_many(&docs), results);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_analyze_files_matches_serial() {
+        let dir = std::env::temp_dir().join("fog_test_par_analyze_files");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let paragraph = "Extraordinary complications arise from miscellaneous circumstances. \
+                         The cat sat on the mat. ";
+        let mut paths: Vec<String> = (0..16)
+            .map(|i| {
+                let path = dir.join(format!("doc{}.txt", i));
+                fs::write(&path, paragraph.repeat(5 * (i + 1))).unwrap();
+                path.to_string_lossy().into_owned()
+            })
+            .collect();
+        paths.push(dir.join("missing.txt").to_string_lossy().into_owned());
+
+        let analyzer = TextAnalyzer::new();
+        let serial = analyzer.analyze_files(&paths);
+        let parallel = analyzer.par_analyze_files(&paths);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(serial.len(), parallel.len());
+        for ((serial_path, serial_result), (parallel_path, parallel_result)) in serial.iter().zip(&parallel) {
+            assert_eq!(serial_path, parallel_path);
+            match (serial_result, parallel_result) {
+                (Ok(a), Ok(b)) => assert_eq!(a, b),
+                (Err(_), Err(_)) => {}
+                _ => panic!("results differ for {}", serial_path),
+            }
+        }
+    }
+
+    #[test]
+    fn test_csv_output() {
+        let analyzer = TextAnalyzer::new();
+        let a = analyzer.analyze_text("The cat sat on the mat.");
+        let b = analyzer.analyze_text("Extraordinary complications arise. Dogs run.");
+        let csv = to_csv(&[("a.txt", &a), ("dir, with comma/b.txt", &b)]);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("path,word_count,sentence_count,"));
+        assert_eq!(lines[0].split(',').count(), TextMetrics::CSV_COLUMNS.len() + 1);
+        assert_eq!(a.csv_values().len(), TextMetrics::CSV_COLUMNS.len());
+        assert!(lines[1].starts_with("a.txt,6,1,"));
+        assert!(lines[1].contains(&format!(",{:.4},", a.flesch_reading_ease)));
+        assert!(lines[2].starts_with("\"dir, with comma/b.txt\",5,2,"));
+    }
+
+    #[test]
+    fn test_display_report() {
+        let metrics = TextAnalyzer::new().analyze_text("The cat sat on the mat.");
+        let report = metrics.to_string();
+        assert!(report.starts_with("Text Analysis Results:\n"));
+        assert!(report.contains("\nWord Count: 6\n"));
+        assert!(report.contains("\nFlesch-Kincaid Grade Level: -1.4\n"));
+        assert!(report.contains("\nSMOG Index: N/A (needs 30+ sentences)\n"));
+        assert!(report.contains("\nRIX: 0.0 (grade 1)\n"));
+        assert!(report.ends_with("\nReading Level: Very Easy (5th grade)"));
+        assert!(format!("{:.2}", metrics).contains("\nFlesch-Kincaid Grade Level: -1.45\n"));
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let analyzer = TextAnalyzer::new();
+        let a = analyzer.analyze_text("The cat sat on the mat.");
+        let b = analyzer.analyze_text("Extraordinary complications arise. Dogs run.");
+        let summary = analyzer.aggregate(&[a.clone(), b.clone()]);
+        assert_eq!(summary.documents, 2);
+
+        let combined = &summary.combined;
+        assert_eq!((combined.word_count, combined.sentence_count), (11, 3));
+        let joined = analyzer.analyze_text("The cat sat on the mat. Extraordinary complications arise. Dogs run.");
+        assert!((combined.flesch_kincaid_grade - joined.flesch_kincaid_grade).abs() < 1e-9);
+        assert!((combined.gunning_fog_index - joined.gunning_fog_index).abs() < 1e-9);
+        // Not the average of the two grades
+        assert!((combined.flesch_kincaid_grade - (a.flesch_kincaid_grade + b.flesch_kincaid_grade) / 2.0).abs() > 0.1);
+
+        let grade = summary.get("flesch_kincaid_grade").unwrap();
+        assert!((grade.mean.unwrap() - (a.flesch_kincaid_grade + b.flesch_kincaid_grade) / 2.0).abs() < 1e-9);
+        assert!((grade.std_dev.unwrap() - (a.flesch_kincaid_grade - b.flesch_kincaid_grade).abs() / 2.0).abs() < 1e-9);
+        assert_eq!(summary.get("smog_index").unwrap().mean, None);
+        assert_eq!(analyzer.aggregate(&[]).combined.word_count, 0);
+    }
+
+    #[test]
+    fn test_smart_punctuation() {
+        let text = "“It’s late,” she said—quietly. The train had gone… We walked home. \
+                    He asked, “Where’s the car?” Nobody knew–or cared.";
+        let plain = TextAnalyzer::new();
+        let normalized = TextAnalyzer::new().with_punctuation_normalization(true);
+        let metrics = normalized.analyze_text(text);
+        // The ellipsis ends "The train had gone" before "We"
+        assert_eq!(metrics.sentence_count, 5);
+        assert_eq!(plain.analyze_text(text).sentence_count, 4);
+        // The dashes still separate "said" from "quietly" and "knew" from "or"
+        assert_eq!(metrics.word_count, 21);
+        assert_eq!(plain.analyze_text(text).word_count, 21);
+        let sentences = normalized.analyze_sentences(text);
+        assert_eq!(sentences[0].text, "\"It's late,\" she said--quietly.");
+        assert_eq!(normalized.analyze_stream(text.as_bytes(), 5).unwrap(), metrics);
+        let mut incremental = IncrementalAnalyzer::new(&normalized);
+        incremental.push(text);
+        assert_eq!(incremental.metrics(), metrics);
+    }
+
+    #[test]
+    fn test_as_map() {
+        let analyzer = TextAnalyzer::new();
+        let metrics = analyzer.analyze_text("The cat sat on the mat. It was happy.");
+        let map = metrics.as_map();
+        // Every column but SMOG, which needs 30 sentences
+        let mut expected: Vec<&str> = TextMetrics::CSV_COLUMNS.iter().copied().filter(|&c| c != "smog_index").collect();
+        expected.sort();
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), expected);
+        assert_eq!(map["word_count"], 9.0);
+        assert_eq!(map["flesch_reading_ease"], metrics.flesch_reading_ease);
+
+        let long = analyzer.analyze_text(&"The cat sat. ".repeat(30));
+        assert_eq!(long.as_map().len(), TextMetrics::CSV_COLUMNS.len());
+        assert_eq!(long.as_map()["smog_index"], long.smog_index.unwrap());
+    }
+
+    #[test]
+    fn test_reading_time() {
+        let metrics = TextAnalyzer::new().analyze_text(&"The cat sat on the mat. ".repeat(100));
+        assert_eq!(metrics.reading_time(200), Duration::from_secs(180));
+        assert_eq!(metrics.reading_time(600), Duration::from_secs(60));
+        // 600 words at 238 per minute
+        assert_eq!(metrics.reading_time_label(), "2 min 31 s");
+        let short = TextAnalyzer::new().analyze_text("The cat sat.");
+        assert_eq!(short.reading_time_label(), "1 s");
+        assert!(short.to_string().contains("\nReading Time: 1 s\n"));
+    }
+
+    #[test]
+    fn test_reliability() {
+        let analyzer = TextAnalyzer::new();
+        assert_eq!(analyzer.analyze_text("Hi there.").reliability(), Reliability::Low);
+        let sentence = "The cat sat on the mat with a hat and a bat. ";
+        // 12 words per sentence
+        assert_eq!(analyzer.analyze_text(&sentence.repeat(9)).reliability(), Reliability::Medium);
+        // Enough words, but in too few sentences
+        let long_sentence = format!("{}.", "word ".repeat(150).trim());
+        assert_eq!(analyzer.analyze_text(&long_sentence).reliability(), Reliability::Low);
+        assert_eq!(analyzer.analyze_text(&sentence.repeat(30)).reliability(), Reliability::High);
+    }
+
+    #[test]
+    fn test_format_metrics_precision() {
+        let metrics = TextAnalyzer::new().analyze_text("The cat sat on the mat.");
+        let whole = metrics.format_metrics(0);
+        assert_eq!(whole.lines().count(), TextMetrics::CSV_COLUMNS.len());
+        assert!(whole.starts_with("word_count: 6\nsentence_count: 1\n"));
+        assert!(whole.contains("\nflesch_kincaid_grade: -1\n"));
+        assert!(whole.contains("\nsmog_index: N/A\n"));
+        assert!(whole.contains("\naverage_words_per_sentence: 6\n"));
+        let precise = metrics.format_metrics(2);
+        assert!(precise.contains("\nflesch_kincaid_grade: -1.45\n"));
+        assert!(precise.contains("\naverage_words_per_sentence: 6.00\n"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_csv_columns_match_json_keys() {
+        let metrics = TextAnalyzer::new().analyze_text("The cat sat on the mat.");
+        let value = serde_json::to_value(&metrics).unwrap();
+        let mut keys: Vec<&str> = value.as_object().unwrap().keys().map(String::as_str).collect();
+        let mut columns = TextMetrics::CSV_COLUMNS.to_vec();
+        keys.sort();
+        columns.sort();
+        assert_eq!(keys, columns);
+    }
+
+    #[test]
+    fn test_ellipses_and_quotes() {
+        let analyzer = TextAnalyzer::new();
+        let sentences = analyzer.analyze_sentences("He paused... then left. She said \"go.\" Wait... What?!");
+        let texts: Vec<&str> = sentences.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, ["He paused... then left.", "She said \"go.\"", "Wait...", "What?!"]);
+        assert_eq!(analyzer.analyze_text("He paused... then left. She said \"go.\"").sentence_count, 2);
+        assert_eq!(analyzer.analyze_text("(It rained.) We stayed in.").sentence_count, 2);
+    }
+
+    struct WordsPerSentence;
+
+    impl ReadabilityFormula for WordsPerSentence {
+        fn name(&self) -> &str {
+            "words_per_sentence"
+        }
+
+        fn score(&self, counts: &RawCounts) -> f64 {
+            counts.word_count as f64 / counts.sentence_count as f64
+        }
+    }
+
+    #[test]
+    fn test_syllable_histogram() {
+        let analyzer = TextAnalyzer::new();
+        let histogram = analyzer.syllable_histogram("The happy animal sat on a little table by the window.");
+        // the, sat, on, a, by, the / happy, little, table, window / animal
+        assert_eq!(histogram, BTreeMap::from([(1, 6), (2, 4), (3, 1)]));
+        assert!(analyzer.syllable_histogram("").is_empty());
+    }
+
+    #[test]
+    fn test_count() {
+        let analyzer = TextAnalyzer::new();
+        let text = "The cat sat on the mat. Extraordinary complications arise!";
+        let counts = analyzer.count(text);
+        assert_eq!(counts.word_count, 9);
+        assert_eq!(counts.sentence_count, 2);
+        assert_eq!(counts.complex_word_count, 2);
+        assert_eq!(counts.letter_count, 48);
+        let metrics = analyzer.analyze_text(text);
+        assert_eq!(counts.syllable_count, metrics.syllable_count);
+        assert_eq!(FleschKincaidGrade.score(&counts), metrics.flesch_kincaid_grade);
+        // Unlike TextMetrics, the raw counts keep a missing terminator visible
+        assert_eq!(analyzer.count("no end here").sentence_count, 0);
+    }
+
+    #[test]
+    fn test_custom_formula() {
+        let analyzer = TextAnalyzer::new().with_formula(WordsPerSentence);
+        let metrics = analyzer.analyze_text("The cat sat on the mat. It was happy.");
+        assert_eq!(metrics.custom.len(), 1);
+        assert_eq!(metrics.custom["words_per_sentence"], 4.5);
+        assert!(TextAnalyzer::new().analyze_text("The cat sat.").custom.is_empty());
+        // No sentence terminator still counts as one sentence
+        assert_eq!(analyzer.analyze_text("no end here").custom["words_per_sentence"], 3.0);
+    }
+
+    #[test]
+    fn test_compare() {
+        let analyzer = TextAnalyzer::new();
+        let draft = "Extraordinary complications arise from miscellaneous circumstances.";
+        let revision = "Odd things can go wrong. We did not plan for them.";
+        let diff = analyzer.compare(draft, revision);
+        assert_eq!(diff.direction, Direction::Easier);
+        assert_eq!(diff.fields.len(), TextMetrics::CSV_COLUMNS.len());
+
+        let words = diff.get("word_count").unwrap();
+        assert_eq!((words.before, words.after, words.delta), (Some(6.0), Some(11.0), Some(5.0)));
+        let smog = diff.get("smog_index").unwrap();
+        assert_eq!(smog.delta, None);
+
+        assert_eq!(analyzer.compare(revision, draft).direction, Direction::Harder);
+        assert_eq!(analyzer.compare(draft, draft).direction, Direction::Unchanged);
+        let printed = diff.to_string();
+        assert!(printed.contains("word_count: 6.0 -> 11.0 (+5.0)"));
+        assert!(printed.ends_with("Overall: easier"));
+    }
+
+    #[test]
+    fn test_forcast() {
+        let analyzer = TextAnalyzer::new();
+        // A form has no sentence punctuation at all
+        let form = "Name:\nDate of birth:\nHome address:\nTelephone number:\nSignature:\n";
+        let metrics = analyzer.analyze_text(form);
+        assert_eq!(metrics.sentence_count, 1);
+        assert_eq!(metrics.word_count, 9);
+        // name, date, of, birth, home = 5 monosyllables
+        assert_eq!(metrics.monosyllable_count, 5);
+        let expected = 20.0 - (5.0 * 150.0 / 9.0) / 10.0;
+        assert!((metrics.forcast_index - expected).abs() < 1e-9);
+        assert_eq!(analyzer.calculate_forcast(form), metrics.forcast_index);
+        assert_eq!(analyzer.calculate_forcast(""), 0.0);
+    }
+
+    #[test]
+    fn test_eflaw() {
+        let analyzer = TextAnalyzer::new();
+        let text = "The cat sat on the mat. Extraordinary circumstances require patience.";
+        let metrics = analyzer.analyze_text(text);
+        // Every word of the first sentence has at most 3 letters
+        assert_eq!(metrics.mini_word_count, 6);
+        assert_eq!(metrics.eflaw_score, (10.0 + 6.0) / 2.0);
+        assert_eq!(analyzer.calculate_eflaw(text), metrics.eflaw_score);
+        assert_eq!(analyzer.calculate_eflaw(""), 0.0);
+    }
+
+    #[test]
+    fn test_incremental_analyzer() {
+        let analyzer = TextAnalyzer::new();
+        let text = "The quick brown fox jumps. Extraordinary complications arise from circumstances! Dr. Smith agrees...";
+        let mut incremental = IncrementalAnalyzer::new(&analyzer);
+        assert_eq!(incremental.metrics(), analyzer.analyze_text(""));
+
+        for piece in text.split_inclusive(['o', ' ', '.']) {
+            incremental.push(piece);
+        }
+        assert_eq!(incremental.metrics(), analyzer.analyze_text(text));
+
+        // Pushing one character at a time splits every word
+        let mut incremental = IncrementalAnalyzer::new(&analyzer);
+        let mut pushed = String::new();
+        for c in text.chars() {
+            incremental.push(c.encode_utf8(&mut [0; 4]));
+            pushed.push(c);
+            assert_eq!(incremental.metrics().word_count, analyzer.analyze_text(&pushed).word_count);
+        }
+        assert_eq!(incremental.metrics(), analyzer.analyze_text(text));
+    }
+
+    fn fixture(name: &str) -> String {
+        format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    #[cfg(feature = "cmudict")]
+    #[test]
+    fn test_cmudict_syllables() {
+        let heuristic = TextAnalyzer::new();
+        let analyzer = TextAnalyzer::new().with_cmudict(fixture("cmudict-sample.txt")).unwrap();
+        assert_eq!(analyzer.count_syllables("business"), 2);
+        assert_eq!(analyzer.count_syllables("Every"), 2);
+        assert_eq!(analyzer.count_syllables("Wednesday"), 2);
+        assert_eq!(analyzer.count_syllables("chocolate"), 2);
+        assert_ne!(heuristic.count_syllables("business"), 2);
+        assert_ne!(heuristic.count_syllables("every"), 2);
+        // Words missing from the dictionary fall back to the heuristic
+        assert_eq!(analyzer.count_syllables("table"), heuristic.count_syllables("table"));
+        assert!(TextAnalyzer::new().with_cmudict(fixture("missing.txt")).is_err());
+    }
+
+    #[test]
+    fn test_utf16_files() {
+        let analyzer = TextAnalyzer::new();
+        let expected = analyzer.analyze_text("The café was full. Extraordinary complications arose!\n");
+        assert_eq!(analyzer.analyze_file(&fixture("utf16le.txt")).unwrap(), expected);
+        let text = analyzer.read_text(&fixture("utf16le.txt")).unwrap();
+        assert_eq!(text, "The café was full. Extraordinary complications arose!\n");
+
+        let mut big_endian = vec![0xFE, 0xFF];
+        for unit in "The cat sat.".encode_utf16() {
+            big_endian.extend_from_slice(&unit.to_be_bytes());
+        }
+        let metrics = analyzer.analyze_stream(big_endian.as_slice(), 4).unwrap();
+        assert_eq!(metrics, analyzer.analyze_text("The cat sat."));
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let bytes: &[u8] = b"\xEF\xBB\xBFThe cat sat. Bad \xFF byte here.";
+        let strict = TextAnalyzer::new().analyze_stream(bytes, 8).unwrap_err();
+        assert_eq!(strict.kind(), io::ErrorKind::InvalidData);
+        let cause = strict.get_ref().and_then(|e| e.downcast_ref::<EncodingError>());
+        assert_eq!(cause, Some(&EncodingError::InvalidUtf8 { offset: 20 }));
+
+        let lossy = TextAnalyzer::new().with_lossy_decoding(true);
+        let metrics = lossy.analyze_stream(bytes, 8).unwrap();
+        assert_eq!(metrics, lossy.analyze_text("The cat sat. Bad \u{FFFD} byte here."));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_round_trip() {
+        let analyzer = TextAnalyzer::new();
+        let metrics = analyzer.analyze_text("The cat sat on the mat. Extraordinary circumstances arise.");
+        let json = metrics.to_json();
+        let parsed: TextMetrics = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, metrics);
+        assert!(json.contains(&format!("\"flesch_reading_ease\":{}", metrics.flesch_reading_ease)));
+    }
+
+    #[test]
+    fn test_dale_chall_without_list() {
+        let analyzer = TextAnalyzer::new();
+        let metrics = analyzer.analyze_text("The cat sat on the extraordinary mat.");
+        assert_eq!(metrics.difficult_word_count, 0);
+        assert_eq!(metrics.dale_chall_score, 0.0);
+    }
+}
+
+// The silent-e rule interacts with vowel grouping, the consonant + "le"
+// exception and the "-ate" split, so its cases are kept together here
+#[cfg(test)]
+mod silent_e_tests {
+    use super::*;
+
+    fn syllables(words: &[&str]) -> Vec<usize> {
+        let analyzer = TextAnalyzer::new();
+        words.iter().map(|word| analyzer.count_syllables(word)).collect()
+    }
+
+    #[test]
+    fn test_single_vowel_words_keep_their_e() {
+        assert_eq!(syllables(&["the", "she", "he", "be", "we", "me"]), [1; 6]);
+    }
+
+    #[test]
+    fn test_silent_final_e() {
+        assert_eq!(syllables(&["make", "time", "cake", "here", "there", "hope"]), [1; 6]);
+        assert_eq!(syllables(&["became", "complete"]), [2, 2]);
+    }
+
+    #[test]
+    fn test_consonant_le_is_voiced() {
+        assert_eq!(syllables(&["table", "bicycle", "little"]), [2, 3, 2]);
+    }
+
+    #[test]
+    fn test_ate_after_a_vowel_is_its_own_syllable() {
+        assert_eq!(syllables(&["create", "recreate", "mediate", "delineate"]), [2, 3, 3, 4]);
+        assert_eq!(syllables(&["gate", "plate"]), [1, 1]);
+    }
+}
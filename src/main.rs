@@ -1,204 +1,646 @@
-use regex::Regex;
+use clap::error::ErrorKind;
+use clap::{ArgAction, CommandFactory, Parser, ValueEnum};
+use fog::{AnalyzerError, Reliability, TextAnalyzer, TextAnalyzerConfig, TextMetrics};
 use std::fs::File;
-use std::io::{self, Read};
-use std::path::Path;
-
-#[derive(Debug)]
-pub struct TextMetrics {
-    word_count: usize,
-    sentence_count: usize,
-    syllable_count: usize,
-    complex_word_count: usize,
-    character_count: usize,
-    gunning_fog_index: f64,
-    flesch_kincaid_grade: f64,
-    flesch_reading_ease: f64,
-    smog_index: f64,
-    average_words_per_sentence: f64,
-    average_syllables_per_word: f64,
-}
-
-pub struct TextAnalyzer {
-    word_pattern: Regex,
-    sentence_pattern: Regex,
-    vowel_pattern: Regex,
-}
-
-impl TextAnalyzer {
-    pub fn new() -> Self {
-        TextAnalyzer {
-            word_pattern: Regex::new(r"\b[a-zA-Z]+\b").unwrap(),
-            sentence_pattern: Regex::new(r"[.!?]+").unwrap(),
-            vowel_pattern: Regex::new(r"[aeiouy]+").unwrap(),
-        }
-    }
-
-    fn count_syllables(&self, word: &str) -> usize {
-        let cleaned_word = word.trim_matches(|c: char| !c.is_alphabetic()).to_lowercase();
-        
-        // Handle special cases
-        if cleaned_word.is_empty() {
-            return 0;
-        }
-        
-        let mut count = self.vowel_pattern.find_iter(&cleaned_word).count();
-        
-        // Adjust for common patterns
-        if cleaned_word.ends_with('e') && count > 1 && !cleaned_word.ends_with("le") {
-            count -= 1;
-        }
-        
-        // Handle consecutive vowels
-        let consecutive_vowels = Regex::new(r"[aeiouy]{2,}").unwrap();
-        count -= consecutive_vowels.find_iter(&cleaned_word).count();
-        
-        // Ensure at least one syllable
-        count.max(1)
-    }
-
-    fn is_complex_word(&self, word: &str, syllable_count: usize) -> bool {
-        let cleaned_word = word.trim_matches(|c: char| !c.is_alphabetic()).to_lowercase();
-        syllable_count >= 3 
-            && !cleaned_word.ends_with("ed") 
-            && !cleaned_word.ends_with("es") 
-            && !cleaned_word.ends_with("ing")
-    }
-
-    pub fn analyze_text(&self, text: &str) -> TextMetrics {
-        let words: Vec<&str> = self.word_pattern.find_iter(text)
-            .map(|m| m.as_str())
-            .collect();
-        
-        let word_count = words.len();
-        let sentence_count = self.sentence_pattern.find_iter(text).count().max(1);
-        let character_count = text.chars().filter(|c| c.is_alphabetic()).count();
-        
-        let mut syllable_count = 0;
-        let mut complex_word_count = 0;
-        
-        for word in &words {
-            let word_syllables = self.count_syllables(word);
-            syllable_count += word_syllables;
-            if self.is_complex_word(word, word_syllables) {
-                complex_word_count += 1;
+use std::io::{self, BufRead, BufWriter, IsTerminal, Read, Write};
+use std::process;
+
+#[derive(Parser)]
+#[command(
+    name = "fog",
+    about = "Readability metrics for plain text",
+    after_help = "Exit status:\n  \
+                  0  every input was analyzed and passed --max-grade\n  \
+                  1  an input or output file could not be read or written, or no\n     \
+                     files matched the given patterns\n  \
+                  2  the arguments were invalid\n  \
+                  3  an input scored above --max-grade"
+)]
+struct Cli {
+    /// Files or glob patterns such as 'docs/**/*.md' to analyze; reads stdin
+    /// when none are given
+    paths: Vec<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Score to print; repeat to select several (default: all)
+    #[arg(long = "metric", value_enum)]
+    metrics: Vec<Metric>,
+
+    /// Replace undecodable bytes in files with U+FFFD instead of failing
+    #[arg(long)]
+    lossy: bool,
+
+    /// Also write the results as JSON to this file
+    #[arg(long, value_name = "PATH")]
+    json_out: Option<String>,
+
+    /// Also write the results as CSV to this file
+    #[arg(long, value_name = "PATH")]
+    csv_out: Option<String>,
+
+    /// Treat each non-empty line of stdin as a separate input
+    #[arg(long, conflicts_with = "paths")]
+    per_line: bool,
+
+    /// Read paragraphs from stdin until EOF (Ctrl-D), scoring each when a
+    /// blank line ends it
+    #[arg(long, conflicts_with_all = ["paths", "per_line"])]
+    repl: bool,
+
+    /// Decimal places for scores in the text report
+    #[arg(long, value_name = "DIGITS", default_value_t = 1)]
+    precision: usize,
+
+    /// Print each text result by filling a template such as
+    /// '{word_count} words, FRE {flesch_reading_ease:.1}' instead of the report;
+    /// placeholders are the CSV column names
+    #[arg(long, conflicts_with = "metrics")]
+    template: Option<String>,
+
+    /// Fail with exit status 3 if any input scores above this grade on the
+    /// selected metrics (default: Flesch-Kincaid)
+    #[arg(long, value_name = "GRADE")]
+    max_grade: Option<f64>,
+
+    /// Log what the analyzer does to stderr; repeat (-vv) to also log every
+    /// word's syllables and classification
+    #[arg(short, long, action = ArgAction::Count)]
+    verbose: u8,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Format {
+    Text,
+    /// One JSON object, or an array of them for several inputs
+    Json,
+    /// One JSON object per line, written as each input finishes
+    Jsonl,
+    Csv,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Metric {
+    GunningFog,
+    FleschKincaid,
+    FleschReadingEase,
+    Smog,
+    ColemanLiau,
+    Ari,
+    DaleChall,
+    Lix,
+    LinsearWrite,
+    Spache,
+    Forcast,
+    Rix,
+    Eflaw,
+}
+
+impl Metric {
+    const ALL: [Metric; 13] = [
+        Metric::GunningFog,
+        Metric::FleschKincaid,
+        Metric::FleschReadingEase,
+        Metric::Smog,
+        Metric::ColemanLiau,
+        Metric::Ari,
+        Metric::DaleChall,
+        Metric::Lix,
+        Metric::LinsearWrite,
+        Metric::Spache,
+        Metric::Forcast,
+        Metric::Rix,
+        Metric::Eflaw,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Metric::GunningFog => "Gunning Fog Index",
+            Metric::FleschKincaid => "Flesch-Kincaid Grade Level",
+            Metric::FleschReadingEase => "Flesch Reading Ease",
+            Metric::Smog => "SMOG Index",
+            Metric::ColemanLiau => "Coleman-Liau Index",
+            Metric::Ari => "Automated Readability Index",
+            Metric::DaleChall => "Dale-Chall Score",
+            Metric::Lix => "LIX",
+            Metric::LinsearWrite => "Linsear Write",
+            Metric::Spache => "Spache Score",
+            Metric::Forcast => "FORCAST",
+            Metric::Rix => "RIX",
+            Metric::Eflaw => "McAlpine EFLAW",
+        }
+    }
+
+    // Field name in TextMetrics, used for JSON keys and CSV headers
+    fn key(self) -> &'static str {
+        match self {
+            Metric::GunningFog => "gunning_fog_index",
+            Metric::FleschKincaid => "flesch_kincaid_grade",
+            Metric::FleschReadingEase => "flesch_reading_ease",
+            Metric::Smog => "smog_index",
+            Metric::ColemanLiau => "coleman_liau_index",
+            Metric::Ari => "automated_readability_index",
+            Metric::DaleChall => "dale_chall_score",
+            Metric::Lix => "lix_index",
+            Metric::LinsearWrite => "linsear_write",
+            Metric::Spache => "spache_score",
+            Metric::Forcast => "forcast_index",
+            Metric::Rix => "rix_index",
+            Metric::Eflaw => "eflaw_score",
+        }
+    }
+
+    // Reading ease, LIX, RIX and EFLAW are not on a grade scale
+    fn is_grade(self) -> bool {
+        !matches!(self, Metric::FleschReadingEase | Metric::Lix | Metric::Rix | Metric::Eflaw)
+    }
+
+    // None when the score is not valid for this text
+    fn value(self, metrics: &TextMetrics) -> Option<f64> {
+        match self {
+            Metric::GunningFog => Some(metrics.gunning_fog_index),
+            Metric::FleschKincaid => Some(metrics.flesch_kincaid_grade),
+            Metric::FleschReadingEase => Some(metrics.flesch_reading_ease),
+            Metric::Smog => metrics.smog_index,
+            Metric::ColemanLiau => Some(metrics.coleman_liau_index),
+            Metric::Ari => Some(metrics.automated_readability_index),
+            Metric::DaleChall => Some(metrics.dale_chall_score),
+            Metric::Lix => Some(metrics.lix_index),
+            Metric::LinsearWrite => Some(metrics.linsear_write),
+            Metric::Spache => Some(metrics.spache_score),
+            Metric::Forcast => Some(metrics.forcast_index),
+            Metric::Rix => Some(metrics.rix_index),
+            Metric::Eflaw => Some(metrics.eflaw_score),
+        }
+    }
+}
+
+// The full report is TextMetrics' Display; a --metric selection lists only
+// the chosen scores in the same layout
+fn write_report(out: &mut dyn Write, metrics: &TextMetrics, selected: &[Metric], precision: usize) -> io::Result<()> {
+    if selected == Metric::ALL {
+        return writeln!(out, "{:.*}", precision, metrics);
+    }
+    writeln!(out, "Text Analysis Results:")?;
+    writeln!(out, "----------------------")?;
+    writeln!(out, "Word Count: {}", metrics.word_count)?;
+    writeln!(out, "Sentence Count: {}", metrics.sentence_count)?;
+    writeln!(out, "Complex Word Count: {}", metrics.complex_word_count)?;
+    writeln!(out, "Average Words per Sentence: {:.*}", precision, metrics.average_words_per_sentence)?;
+    writeln!(out, "Average Syllables per Word: {:.*}", precision, metrics.average_syllables_per_word)?;
+    writeln!(out, "Reading Time: {}", metrics.reading_time_label())?;
+    writeln!(out, "\nReadability Scores:")?;
+    writeln!(out, "------------------")?;
+    for metric in selected {
+        match metric.value(metrics) {
+            Some(value) if *metric == Metric::Rix => {
+                writeln!(out, "{}: {:.*} ({})", metric.label(), precision, value, metrics.rix_grade_label())?
             }
+            Some(value) => writeln!(out, "{}: {:.*}", metric.label(), precision, value)?,
+            None => writeln!(out, "{}: N/A (needs 30+ sentences)", metric.label())?,
         }
+    }
+    writeln!(out, "\nReading Level: {}", metrics.reading_level_label())
+}
 
-        let average_words_per_sentence = word_count as f64 / sentence_count as f64;
-        let average_syllables_per_word = if word_count > 0 {
-            syllable_count as f64 / word_count as f64
-        } else {
-            0.0
-        };
+fn json_object(source: &str, metrics: &TextMetrics, selected: &[Metric]) -> serde_json::Value {
+    let mut value = serde_json::to_value(metrics).expect("TextMetrics is always serializable");
+    let object = value.as_object_mut().expect("TextMetrics serializes to an object");
+    for metric in Metric::ALL.iter().filter(|m| !selected.contains(m)) {
+        object.remove(metric.key());
+    }
+    object.insert("path".to_string(), source.into());
+    value
+}
+
+fn write_csv_header(out: &mut dyn Write, selected: &[Metric]) -> io::Result<()> {
+    let columns = TextMetrics::CSV_COLUMNS
+        .iter()
+        .copied()
+        .filter(|column| is_selected_column(column, selected));
+    writeln!(out, "path,{}", columns.collect::<Vec<_>>().join(","))
+}
+
+fn write_csv_row(out: &mut dyn Write, source: &str, metrics: &TextMetrics, selected: &[Metric]) -> io::Result<()> {
+    let values = TextMetrics::CSV_COLUMNS
+        .iter()
+        .zip(metrics.csv_values())
+        .filter(|(column, _)| is_selected_column(column, selected))
+        .map(|(_, value)| value);
+    writeln!(out, "{},{}", fog::csv_escape(source), values.collect::<Vec<_>>().join(","))
+}
+
+// Counts and averages are always shown; scores only when selected
+fn is_selected_column(column: &str, selected: &[Metric]) -> bool {
+    match Metric::ALL.iter().find(|m| m.key() == column) {
+        Some(metric) => selected.contains(metric),
+        None => true,
+    }
+}
+
+// One destination for the results, in one format. Stdout gets `--format`;
+// `--json-out` and `--csv-out` add files written from the same analysis.
+struct Output<'a> {
+    name: String,
+    format: Format,
+    out: Box<dyn Write>,
+    selected: &'a [Metric],
+    precision: usize,
+    template: Option<&'a str>,
+    // Several inputs get headers in text and an array in JSON
+    multiple: bool,
+    written: usize,
+}
 
-        TextMetrics {
-            word_count,
-            sentence_count,
-            syllable_count,
-            complex_word_count,
-            character_count,
-            gunning_fog_index: self.calculate_gunning_fog(word_count, sentence_count, complex_word_count),
-            flesch_kincaid_grade: self.calculate_flesch_kincaid_grade(word_count, sentence_count, syllable_count),
-            flesch_reading_ease: self.calculate_flesch_reading_ease(word_count, sentence_count, syllable_count),
-            smog_index: self.calculate_smog(sentence_count, complex_word_count),
-            average_words_per_sentence,
-            average_syllables_per_word,
+impl<'a> Output<'a> {
+    fn begin(&mut self) -> io::Result<()> {
+        match self.format {
+            Format::Csv => write_csv_header(&mut self.out, self.selected),
+            Format::Json if self.multiple => writeln!(self.out, "["),
+            _ => Ok(()),
         }
     }
 
-    fn calculate_gunning_fog(&self, words: usize, sentences: usize, complex_words: usize) -> f64 {
-        if words == 0 || sentences == 0 {
-            return 0.0;
+    fn write(&mut self, source: &str, metrics: &TextMetrics) -> io::Result<()> {
+        let selected = self.selected;
+        match (self.format, self.template) {
+            // One filled template per input, under the same headers as the report
+            (Format::Text, Some(template)) => {
+                if self.multiple {
+                    writeln!(self.out, "==> {} <==", source)?;
+                }
+                // Checked against an empty text before any input is read
+                writeln!(self.out, "{}", metrics.render(template).map_err(io::Error::other)?)?;
+            }
+            (Format::Text, None) => {
+                if self.written > 0 {
+                    writeln!(self.out)?;
+                }
+                if self.multiple {
+                    writeln!(self.out, "==> {} <==", source)?;
+                }
+                write_report(&mut self.out, metrics, selected, self.precision)?;
+                if metrics.reliability() == Reliability::Low {
+                    writeln!(
+                        self.out,
+                        "\nNote: with {} words in {} sentences these scores are unreliable; \
+                         they need at least 100 words in 3 sentences.",
+                        metrics.word_count, metrics.sentence_count
+                    )?;
+                }
+            }
+            (Format::Json, _) if self.multiple => {
+                let separator = if self.written > 0 { ",\n" } else { "" };
+                write!(self.out, "{}  {}", separator, json_object(source, metrics, selected))?;
+            }
+            (Format::Json | Format::Jsonl, _) => writeln!(self.out, "{}", json_object(source, metrics, selected))?,
+            (Format::Csv, _) => write_csv_row(&mut self.out, source, metrics, selected)?,
         }
-        0.4 * ((words as f64 / sentences as f64) + 100.0 * (complex_words as f64 / words as f64))
+        self.written += 1;
+        // Streamed formats should reach a pipe as each input finishes
+        self.out.flush()
     }
 
-    fn calculate_flesch_kincaid_grade(&self, words: usize, sentences: usize, syllables: usize) -> f64 {
-        if words == 0 || sentences == 0 {
-            return 0.0;
+    fn finish(&mut self) -> io::Result<()> {
+        if self.format == Format::Json && self.multiple {
+            if self.written > 0 {
+                writeln!(self.out)?;
+            }
+            writeln!(self.out, "]")?;
         }
-        0.39 * (words as f64 / sentences as f64) + 11.8 * (syllables as f64 / words as f64) - 15.59
+        self.out.flush()
     }
+}
 
-    fn calculate_flesch_reading_ease(&self, words: usize, sentences: usize, syllables: usize) -> f64 {
-        if words == 0 || sentences == 0 {
-            return 0.0;
+// Runs one step on every output; an output that fails is reported once and
+// dropped, so the others still get the remaining results. Returns false if
+// any output failed.
+fn write_all(outputs: &mut Vec<Output>, mut step: impl FnMut(&mut Output) -> io::Result<()>) -> bool {
+    let before = outputs.len();
+    outputs.retain_mut(|output| match step(output) {
+        Ok(()) => true,
+        Err(error) => {
+            eprintln!("fog: {}: {}", output.name, error);
+            false
         }
-        206.835 - 1.015 * (words as f64 / sentences as f64) - 84.6 * (syllables as f64 / words as f64)
+    });
+    outputs.len() == before
+}
+
+// Expands glob patterns, which shells on Windows leave to the program, in
+// sorted order. Arguments without glob characters are kept as given so a
+// missing file is still reported as an error when it is read.
+fn expand_paths(args: &[String]) -> Vec<String> {
+    let mut paths = Vec::new();
+    for arg in args {
+        if !arg.contains(['*', '?', '[']) {
+            paths.push(arg.clone());
+            continue;
+        }
+        let matches = match glob::glob(arg) {
+            Ok(matches) => matches,
+            Err(error) => {
+                eprintln!("fog: {}: invalid pattern: {}", arg, error);
+                continue;
+            }
+        };
+        let before = paths.len();
+        for entry in matches {
+            match entry {
+                Ok(path) if path.is_file() => paths.push(path.display().to_string()),
+                Ok(_) => {}
+                Err(error) => eprintln!("fog: {}", error),
+            }
+        }
+        if paths.len() == before {
+            eprintln!("fog: warning: {} matched no files", arg);
+        }
+    }
+    paths
+}
+
+// Prints the library's log records as "fog: debug: ..."; other crates stay quiet
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.target().starts_with("fog")
     }
 
-    fn calculate_smog(&self, sentences: usize, complex_words: usize) -> f64 {
-        if sentences < 30 {
-            return 0.0; // SMOG is only valid for 30+ sentences
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("fog: {}: {}", record.level().as_str().to_lowercase(), record.args());
         }
-        1.0430 * f64::sqrt(complex_words as f64 * (30.0 / sentences as f64)) + 3.1291
     }
 
-    pub fn analyze_file(&self, filepath: &str) -> io::Result<TextMetrics> {
-        let path = Path::new(filepath);
-        if !path.exists() {
-            return Err(io::Error::new(io::ErrorKind::NotFound, "File not found"));
+    fn flush(&self) {}
+}
+
+// Warnings are shown even without -v
+fn init_logging(verbosity: u8) {
+    let level = match verbosity {
+        0 => log::LevelFilter::Warn,
+        1 => log::LevelFilter::Debug,
+        _ => log::LevelFilter::Trace,
+    };
+    if log::set_logger(&StderrLogger).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
+// Settings come from `.readability.toml` in the working directory when it
+// exists; flags given on the command line take precedence
+fn load_analyzer() -> io::Result<TextAnalyzer> {
+    match TextAnalyzerConfig::discover(".")? {
+        Some(config) => {
+            log::debug!("using ./{}", TextAnalyzerConfig::FILE_NAME);
+            TextAnalyzer::from_config(&config)
         }
+        None => Ok(TextAnalyzer::new()),
+    }
+}
 
-        let mut file = File::open(path)?;
-        let mut contents = String::new();
-        file.read_to_string(&mut contents)?;
-        
-        Ok(self.analyze_text(&contents))
+fn analyze_path(analyzer: &TextAnalyzer, path: &str) -> Result<TextMetrics, AnalyzerError> {
+    #[cfg(feature = "epub")]
+    if path.ends_with(".epub") {
+        return Ok(analyzer.analyze_epub(path)?);
     }
+    analyzer.analyze_file(path)
+}
+
+// The text `analyze_path` analyzed, read again in full
+fn read_path(analyzer: &TextAnalyzer, path: &str) -> Result<String, AnalyzerError> {
+    #[cfg(feature = "epub")]
+    if path.ends_with(".epub") {
+        return Ok(analyzer.read_epub_text(path)?);
+    }
+    analyzer.read_text(path)
+}
+
+// Piped input, e.g. `cat file.txt | fog`
+fn read_stdin() -> io::Result<String> {
+    let mut contents = String::new();
+    io::stdin().read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+// Paragraphs of stdin as they are finished by a blank line or EOF, named
+// "-:N" after their first line. Prompts on stderr when stdin is a terminal.
+fn read_paragraphs() -> impl Iterator<Item = (String, io::Result<String>)> {
+    let interactive = io::stdin().is_terminal();
+    if interactive {
+        eprintln!("fog: type or paste text; a blank line scores it, Ctrl-D quits");
+    }
+    let mut lines = io::stdin().lock().lines();
+    let mut line_number = 0;
+    std::iter::from_fn(move || {
+        let mut paragraph = String::new();
+        let mut start = line_number + 1;
+        loop {
+            if interactive && paragraph.is_empty() {
+                eprint!("> ");
+            }
+            let line = match lines.next() {
+                Some(Ok(line)) => line,
+                Some(Err(error)) => return Some((format!("-:{}", line_number + 1), Err(error))),
+                None if paragraph.is_empty() => {
+                    if interactive {
+                        eprintln!();
+                    }
+                    return None;
+                }
+                None => break,
+            };
+            line_number += 1;
+            if !line.trim().is_empty() {
+                paragraph.push_str(&line);
+                paragraph.push('\n');
+            } else if !paragraph.is_empty() {
+                break;
+            } else {
+                start = line_number + 1;
+            }
+        }
+        Some((format!("-:{}", start), Ok(paragraph)))
+    })
+}
+
+// Reports each gated metric above `max_grade` on stderr, with the sentence
+// that scores highest on it. Returns true if the input failed the gate.
+fn check_grade(
+    analyzer: &TextAnalyzer,
+    source: &str,
+    text: Option<&str>,
+    metrics: &TextMetrics,
+    gated: &[Metric],
+    max_grade: f64,
+) -> bool {
+    let exceeded: Vec<(Metric, f64)> = gated
+        .iter()
+        .filter_map(|&metric| Some((metric, metric.value(metrics)?)))
+        .filter(|&(_, value)| value > max_grade)
+        .collect();
+    if exceeded.is_empty() {
+        return false;
+    }
+    // Files were streamed, so only the failing ones are read again
+    let text = match text {
+        Some(text) => text.to_string(),
+        None => read_path(analyzer, source).unwrap_or_default(),
+    };
+    // Scored once for every metric; ties go to the longer sentence, as in
+    // `TextAnalyzer::hardest_sentences`
+    let sentences = analyzer.score_sentences(&text);
+    for (metric, value) in exceeded {
+        eprintln!("fog: {}: {} is {:.1}, above --max-grade {}", source, metric.label(), value, max_grade);
+        let hardest = sentences
+            .iter()
+            .filter_map(|(sentence, metrics)| Some((metric.value(metrics)?, metrics.word_count, sentence)))
+            .max_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+        if let Some((score, _, sentence)) = hardest {
+            eprintln!("  hardest sentence ({:.1}): {}", score, sentence);
+        }
+    }
+    true
 }
 
 fn main() {
-    let analyzer = TextAnalyzer::new();
-    
-    // Example text
-    let sample_text = "The quick brown fox jumps over the lazy dog. \
-                       This is a simple sentence to demonstrate the algorithm. \
-                       Extraordinary complications arise from miscellaneous circumstances. \
-                       The complexity of this text should be relatively moderate.";
-    
-    let metrics = analyzer.analyze_text(sample_text);
-    
-    println!("Text Analysis Results:");
-    println!("----------------------");
-    println!("Word Count: {}", metrics.word_count);
-    println!("Sentence Count: {}", metrics.sentence_count);
-    println!("Complex Word Count: {}", metrics.complex_word_count);
-    println!("Average Words per Sentence: {:.1}", metrics.average_words_per_sentence);
-    println!("Average Syllables per Word: {:.1}", metrics.average_syllables_per_word);
-    println!("\nReadability Scores:");
-    println!("------------------");
-    println!("Gunning Fog Index: {:.1}", metrics.gunning_fog_index);
-    println!("Flesch-Kincaid Grade Level: {:.1}", metrics.flesch_kincaid_grade);
-    println!("Flesch Reading Ease: {:.1}", metrics.flesch_reading_ease);
-    println!("SMOG Index: {:.1}", metrics.smog_index);
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_empty_text() {
-        let analyzer = TextAnalyzer::new();
-        let metrics = analyzer.analyze_text("");
-        assert_eq!(metrics.word_count, 0);
-        assert_eq!(metrics.gunning_fog_index, 0.0);
-    }
-
-    #[test]
-    fn test_simple_sentence() {
-        let analyzer = TextAnalyzer::new();
-        let metrics = analyzer.analyze_text("The cat sat on the mat.");
-        assert_eq!(metrics.word_count, 6);
-        assert_eq!(metrics.sentence_count, 1);
-    }
-
-    #[test]
-    fn test_syllable_counting() {
-        let analyzer = TextAnalyzer::new();
-        assert_eq!(analyzer.count_syllables("cat"), 1);
-        assert_eq!(analyzer.count_syllables("water"), 2);
-        assert_eq!(analyzer.count_syllables("beautiful"), 3);
-    }
-}
\ No newline at end of file
+    let cli = Cli::parse();
+    init_logging(cli.verbose);
+    let analyzer = match load_analyzer() {
+        Ok(analyzer) if cli.lossy => analyzer.with_lossy_decoding(true),
+        Ok(analyzer) => analyzer,
+        Err(error) => {
+            eprintln!("fog: {}: {}", TextAnalyzerConfig::FILE_NAME, error);
+            process::exit(1);
+        }
+    };
+    let selected: Vec<Metric> = if cli.metrics.is_empty() {
+        Metric::ALL.to_vec()
+    } else {
+        cli.metrics.clone()
+    };
+
+    // Each input is analyzed just before its output is written, so results
+    // stream out instead of being held for the whole run
+    // Stdin can't be read twice, so its text is kept for the grade check
+    type Analyzed = (String, Result<TextMetrics, AnalyzerError>, Option<String>);
+    let (results, multiple): (Box<dyn Iterator<Item = Analyzed>>, bool) = if cli.per_line {
+        // Inputs are named "-:N" after their line number
+        let lines = io::stdin().lock().lines().enumerate().filter_map(|(index, line)| {
+            let source = format!("-:{}", index + 1);
+            match line {
+                Ok(line) if line.trim().is_empty() => None,
+                Ok(line) => Some((source, Ok(analyzer.analyze_text(&line)), Some(line))),
+                Err(error) => Some((source, Err(error.into()), None)),
+            }
+        });
+        (Box::new(lines), true)
+    } else if cli.repl {
+        let paragraphs = read_paragraphs().map(|(source, paragraph)| match paragraph {
+            Ok(text) => (source, Ok(analyzer.analyze_text(&text)), Some(text)),
+            Err(error) => (source, Err(error.into()), None),
+        });
+        // Each report stands alone, as for a single input
+        (Box::new(paragraphs), false)
+    } else if cli.paths.is_empty() {
+        if io::stdin().is_terminal() {
+            Cli::command()
+                .error(ErrorKind::MissingRequiredArgument, "no input; give a file path or pipe text to stdin")
+                .exit();
+        }
+        let result = match read_stdin() {
+            Ok(text) => ("-".to_string(), Ok(analyzer.analyze_text(&text)), Some(text)),
+            Err(error) => ("-".to_string(), Err(error.into()), None),
+        };
+        (Box::new(std::iter::once(result)), false)
+    } else {
+        let paths = expand_paths(&cli.paths);
+        if paths.is_empty() {
+            eprintln!("fog: no files to analyze");
+            process::exit(1);
+        }
+        let multiple = paths.len() > 1;
+        let files = paths.into_iter().map(|path| {
+            let result = analyze_path(&analyzer, &path);
+            (path, result, None)
+        });
+        (Box::new(files), multiple)
+    };
+
+    let gated: Vec<Metric> = if cli.metrics.is_empty() { vec![Metric::FleschKincaid] } else { cli.metrics.clone() };
+    if cli.max_grade.is_some() {
+        if let Some(metric) = gated.iter().find(|metric| !metric.is_grade()) {
+            Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    format!("--max-grade needs grade-level metrics; {} is not one", metric.label()),
+                )
+                .exit();
+        }
+    }
+
+    if let Some(template) = &cli.template {
+        if let Err(error) = analyzer.analyze_text("").render(template) {
+            Cli::command().error(ErrorKind::InvalidValue, format!("invalid --template: {}", error)).exit();
+        }
+    }
+
+    let mut outputs = vec![Output {
+        name: "stdout".to_string(),
+        format: cli.format,
+        out: Box::new(io::stdout()),
+        selected: &selected,
+        precision: cli.precision,
+        template: cli.template.as_deref(),
+        multiple,
+        written: 0,
+    }];
+    let files = [(Format::Json, &cli.json_out), (Format::Csv, &cli.csv_out)];
+    for (format, path) in files {
+        let Some(path) = path else { continue };
+        match File::create(path) {
+            Ok(file) => outputs.push(Output {
+                name: path.clone(),
+                format,
+                out: Box::new(BufWriter::new(file)),
+                selected: &selected,
+                precision: cli.precision,
+                template: None,
+                multiple,
+                written: 0,
+            }),
+            Err(error) => {
+                eprintln!("fog: {}: {}", path, error);
+                process::exit(1);
+            }
+        }
+    }
+
+    let mut failed = !write_all(&mut outputs, |output| output.begin());
+    let mut above_grade = false;
+    for (source, result, text) in results {
+        match &result {
+            Ok(metrics) => {
+                failed |= !write_all(&mut outputs, |output| output.write(&source, metrics));
+                if let Some(max_grade) = cli.max_grade {
+                    above_grade |= check_grade(&analyzer, &source, text.as_deref(), metrics, &gated, max_grade);
+                }
+            }
+            Err(error) => {
+                eprintln!("fog: {}: {}", source, error);
+                failed = true;
+            }
+        }
+    }
+    failed |= !write_all(&mut outputs, |output| output.finish());
+
+    if failed {
+        process::exit(1);
+    }
+    if above_grade {
+        process::exit(3);
+    }
+}
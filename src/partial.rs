@@ -0,0 +1,182 @@
+use crate::formula::{
+    AutomatedReadability, ColemanLiau, DaleChall, Eflaw, FleschKincaidGrade, FleschReadingEase, Forcast,
+    GunningFog, LinsearWrite, Lix, ReadabilityFormula, Rix, Smog, Spache,
+};
+use crate::{RawCounts, SmogMode, TextAnalyzer};
+use std::ops::{BitOr, BitOrAssign};
+
+// A set of built-in formulas, combined with `|`:
+//
+//     MetricSet::FLESCH_READING_EASE | MetricSet::SMOG
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MetricSet(u16);
+
+impl MetricSet {
+    pub const GUNNING_FOG: Self = Self(1);
+    pub const FLESCH_KINCAID: Self = Self(1 << 1);
+    pub const FLESCH_READING_EASE: Self = Self(1 << 2);
+    pub const SMOG: Self = Self(1 << 3);
+    pub const COLEMAN_LIAU: Self = Self(1 << 4);
+    pub const AUTOMATED_READABILITY: Self = Self(1 << 5);
+    pub const DALE_CHALL: Self = Self(1 << 6);
+    pub const SPACHE: Self = Self(1 << 7);
+    pub const LIX: Self = Self(1 << 8);
+    pub const RIX: Self = Self(1 << 9);
+    pub const LINSEAR_WRITE: Self = Self(1 << 10);
+    pub const FORCAST: Self = Self(1 << 11);
+    pub const EFLAW: Self = Self(1 << 12);
+    pub const ALL: Self = Self((1 << 13) - 1);
+
+    // The formulas that need every word's syllables counted, the bulk of
+    // the work in an analysis
+    pub(crate) const SYLLABLE_BASED: Self = Self(
+        Self::GUNNING_FOG.0
+            | Self::FLESCH_KINCAID.0
+            | Self::FLESCH_READING_EASE.0
+            | Self::SMOG.0
+            | Self::LINSEAR_WRITE.0
+            | Self::FORCAST.0,
+    );
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for MetricSet {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for MetricSet {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+// The scores chosen with a MetricSet. Unselected scores are None, as are
+// those that can't be computed: SMOG under 30 sentences, and Dale-Chall and
+// Spache without a word list. Counts that no selected formula needs are 0.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PartialMetrics {
+    pub counts: RawCounts,
+    pub gunning_fog_index: Option<f64>,
+    pub flesch_kincaid_grade: Option<f64>,
+    pub flesch_reading_ease: Option<f64>,
+    pub smog_index: Option<f64>,
+    pub coleman_liau_index: Option<f64>,
+    pub automated_readability_index: Option<f64>,
+    pub dale_chall_score: Option<f64>,
+    pub spache_score: Option<f64>,
+    pub lix_index: Option<f64>,
+    pub rix_index: Option<f64>,
+    pub linsear_write: Option<f64>,
+    pub forcast_index: Option<f64>,
+    pub eflaw_score: Option<f64>,
+}
+
+impl TextAnalyzer {
+    // Like `analyze_text` for just the formulas in `metrics`, skipping the
+    // syllable counting and word list lookups they don't need. Formulas added
+    // with `with_formula` are not run.
+    pub fn analyze_text_with(&self, text: &str, metrics: MetricSet) -> PartialMetrics {
+        let text = self.prepare(text);
+        let mut counts = RawCounts::default();
+        self.accumulate_selected(&mut counts, &text, metrics);
+        let raw = counts;
+        // Every formula sees at least one sentence, as in `analyze_text`
+        let counts = RawCounts { sentence_count: counts.sentence_count.max(1), ..counts };
+
+        // CJK mode counts no syllables
+        let score = |metric: MetricSet, formula: &dyn ReadabilityFormula| {
+            let counted = !(self.cjk && MetricSet::SYLLABLE_BASED.contains(metric));
+            (metrics.contains(metric) && counted).then(|| formula.score(&counts))
+        };
+        let smog = if counts.sentence_count < 30 || self.cjk {
+            None
+        } else if self.smog_mode == SmogMode::Sampled {
+            metrics.contains(MetricSet::SMOG).then(|| self.sampled_smog(&text))
+        } else {
+            score(MetricSet::SMOG, &Smog)
+        };
+        PartialMetrics {
+            counts: raw,
+            gunning_fog_index: score(MetricSet::GUNNING_FOG, &GunningFog),
+            flesch_kincaid_grade: score(MetricSet::FLESCH_KINCAID, &FleschKincaidGrade),
+            flesch_reading_ease: score(MetricSet::FLESCH_READING_EASE, &FleschReadingEase),
+            smog_index: smog,
+            coleman_liau_index: score(MetricSet::COLEMAN_LIAU, &ColemanLiau),
+            automated_readability_index: score(MetricSet::AUTOMATED_READABILITY, &AutomatedReadability),
+            dale_chall_score: score(MetricSet::DALE_CHALL, &DaleChall).filter(|_| self.dale_chall_list.is_some()),
+            spache_score: score(MetricSet::SPACHE, &Spache).filter(|_| self.spache_list.is_some()),
+            lix_index: score(MetricSet::LIX, &Lix),
+            rix_index: score(MetricSet::RIX, &Rix),
+            linsear_write: score(MetricSet::LINSEAR_WRITE, &LinsearWrite),
+            forcast_index: score(MetricSet::FORCAST, &Forcast),
+            eflaw_score: score(MetricSet::EFLAW, &Eflaw),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_analyze_text_with() {
+        let text = "The quick brown fox jumps over the lazy dog. Extraordinary circumstances require patience.";
+        let analyzer = TextAnalyzer::new();
+        let full = analyzer.analyze_text(text);
+
+        let ease = analyzer.analyze_text_with(text, MetricSet::FLESCH_READING_EASE);
+        assert_eq!(ease.flesch_reading_ease, Some(full.flesch_reading_ease));
+        assert_eq!(ease.gunning_fog_index, None);
+        assert_eq!(ease.coleman_liau_index, None);
+
+        // Letter-based formulas leave the syllables uncounted
+        let letters = analyzer.analyze_text_with(text, MetricSet::COLEMAN_LIAU | MetricSet::LIX);
+        assert_eq!(letters.coleman_liau_index, Some(full.coleman_liau_index));
+        assert_eq!(letters.lix_index, Some(full.lix_index));
+        assert_eq!(letters.counts.syllable_count, 0);
+        assert_eq!(letters.counts.word_count, full.word_count);
+
+        let all = analyzer.analyze_text_with(text, MetricSet::ALL);
+        assert_eq!(all.counts, analyzer.count(text));
+        assert_eq!(all.gunning_fog_index, Some(full.gunning_fog_index));
+        assert_eq!(all.forcast_index, Some(full.forcast_index));
+        // Under 30 sentences and without word lists
+        assert_eq!((all.smog_index, all.dale_chall_score, all.spache_score), (None, None, None));
+
+        let listed = TextAnalyzer::new().with_dale_chall_list(HashSet::from(["the".to_string()]));
+        let dale_chall = listed.analyze_text_with(text, MetricSet::DALE_CHALL);
+        assert_eq!(dale_chall.dale_chall_score, Some(listed.analyze_text(text).dale_chall_score));
+        assert!(analyzer.analyze_text_with(text, MetricSet::empty()).flesch_kincaid_grade.is_none());
+    }
+
+    #[test]
+    fn test_metric_set() {
+        let mut set = MetricSet::SMOG | MetricSet::RIX;
+        assert!(set.contains(MetricSet::SMOG));
+        assert!(!set.contains(MetricSet::SMOG | MetricSet::LIX));
+        assert!(set.intersects(MetricSet::SMOG | MetricSet::LIX));
+        set |= MetricSet::LIX;
+        assert!(MetricSet::ALL.contains(set));
+        assert!(MetricSet::default().is_empty());
+    }
+}
@@ -0,0 +1,258 @@
+use crate::HYPHENS;
+use regex::Regex;
+use std::sync::LazyLock;
+
+static INLINE_CODE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"`[^`\n]*`").unwrap());
+static IMAGE_OR_LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"!?\[([^\]\n]*)\](?:\([^)\n]*\)|\[[^\]\n]*\])").unwrap());
+static LINK_DEFINITION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^[ \t]{0,3}\[[^\]\n]+\]:[ \t]*\S.*$").unwrap());
+static HEADING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^[ \t]{0,3}#{1,6}[ \t]+|[ \t]+#+[ \t]*$").unwrap());
+static EMPHASIS: LazyLock<[Regex; 5]> = LazyLock::new(|| {
+    [
+        Regex::new(r"\*\*(\S(?:[^*\n]*\S)?)\*\*").unwrap(),
+        Regex::new(r"__(\S(?:[^_\n]*\S)?)__").unwrap(),
+        Regex::new(r"~~(\S(?:[^~\n]*\S)?)~~").unwrap(),
+        Regex::new(r"\*(\S(?:[^*\n]*\S)?)\*").unwrap(),
+        Regex::new(r"\b_(\S(?:[^_\n]*\S)?)_\b").unwrap(),
+    ]
+});
+
+static SCRIPT_OR_STYLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<(script|style)\b.*?</(script|style)\s*>|<!--.*?-->").unwrap());
+static TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+static ENTITY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").unwrap());
+static WHITESPACE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s+").unwrap());
+
+static LINE_END_HYPHEN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\p{L}+)[-\u{2010}\u{ad}][ \t]*\r?\n[ \t]*(\p{L})").unwrap());
+
+// "[12]", "[3, 4]", "[5-7]", Markdown's "[^note]" and superscript numbers
+static REFERENCE_MARKER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[ \t]*(?:\[\d+(?:\s*[,–-]\s*\d+)*\]|\[\^[^\]\s]+\]|[¹²³⁰⁴-⁹]+)").unwrap());
+// "(Smith, 2020)", "(Smith and Lee 2019; Chen, 2021a)", "(Smith et al., 2020, p. 4)"
+static AUTHOR_YEAR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"[ \t]*\((?:[A-Z][^()\n]*?,?\s+(?:1[5-9]|20)\d{2}[a-z]?(?:,\s*pp?\.\s*\d+(?:[–-]\d+)?)?;?\s*)+\)").unwrap()
+});
+
+// Prefixes that keep their hyphen in compounds ("self-esteem", "well-known")
+const HYPHENATED_PREFIXES: &[&str] = &["all", "ex", "half", "ill", "self", "well"];
+
+// Reduces HTML to its visible text: script, style and comment contents are
+// dropped, tags become spaces, common entities are decoded and runs of
+// whitespace collapse to a single space
+pub fn strip_html(text: &str) -> String {
+    let text = SCRIPT_OR_STYLE.replace_all(text, " ");
+    let text = TAG.replace_all(&text, " ");
+    let text = ENTITY.replace_all(&text, |caps: &regex::Captures| {
+        decode_entity(&caps[1]).map_or_else(|| caps[0].to_string(), String::from)
+    });
+    WHITESPACE.replace_all(&text, " ").trim().to_string()
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    let decoded = match entity {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        _ => {
+            let code = match entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => entity.strip_prefix('#')?.parse().ok()?,
+            };
+            return char::from_u32(code);
+        }
+    };
+    Some(decoded)
+}
+
+// Reduces Markdown to its prose: fenced code blocks and inline code are
+// dropped, links and images keep only their text, and heading and emphasis
+// markers are removed
+pub fn strip_markdown(text: &str) -> String {
+    let text = strip_fenced_code(text);
+    let text = INLINE_CODE.replace_all(&text, "");
+    let text = LINK_DEFINITION.replace_all(&text, "");
+    let text = IMAGE_OR_LINK.replace_all(&text, "$1");
+    let mut text = HEADING.replace_all(&text, "").into_owned();
+    for pattern in EMPHASIS.iter() {
+        text = pattern.replace_all(&text, "$1").into_owned();
+    }
+    text
+}
+
+// Removes ``` and ~~~ fenced blocks; an unclosed fence runs to the end
+pub(crate) fn strip_fenced_code(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut fence: Option<&str> = None;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        match fence {
+            Some(marker) => {
+                if trimmed.starts_with(marker) {
+                    fence = None;
+                }
+            }
+            None if trimmed.starts_with("```") => fence = Some("```"),
+            None if trimmed.starts_with("~~~") => fence = Some("~~~"),
+            None => result.push_str(line),
+        }
+    }
+    result
+}
+
+// Removes fenced code blocks and indented (four spaces or a tab) code
+// blocks. As in Markdown, an indented line only starts a code block after a
+// blank line, so wrapped list items and paragraphs are kept.
+pub fn strip_code(text: &str) -> String {
+    let text = strip_fenced_code(text);
+    let mut result = String::with_capacity(text.len());
+    let mut previous_blank = true;
+    let mut in_code = false;
+    for line in text.split_inclusive('\n') {
+        let blank = line.trim().is_empty();
+        let indented = line.starts_with("    ") || line.starts_with('\t');
+        in_code = !blank && indented && (previous_blank || in_code) || blank && in_code;
+        if !in_code {
+            result.push_str(line);
+        }
+        previous_blank = blank;
+    }
+    result
+}
+
+// Maps word-processor punctuation to ASCII: curly quotes become straight
+// ones, em and en dashes "--" so the words around them stay apart, Unicode
+// hyphens "-", the ellipsis character "..." and non-breaking spaces plain
+// spaces
+pub fn normalize_punctuation(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '‘' | '’' | '‚' | '‛' | '′' => result.push('\''),
+            '“' | '”' | '„' | '‟' | '″' => result.push('"'),
+            '—' | '―' | '–' => result.push_str("--"),
+            '‐' | '‑' => result.push('-'),
+            '…' => result.push_str("..."),
+            '\u{a0}' | '\u{202f}' => result.push(' '),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+// Rejoins words hyphenated across a line break, as in text extracted from
+// PDFs: "inter-\nnational" becomes "international". Compounds whose hyphen
+// belongs to the word, those starting with a prefix such as "self" or "well",
+// continuing with a capital ("anti-\nAmerican") or already hyphenated
+// ("mother-in-\nlaw"), keep the hyphen and lose only the line break.
+pub fn rejoin_hyphenated_lines(text: &str) -> String {
+    LINE_END_HYPHEN
+        .replace_all(text, |caps: &regex::Captures| {
+            let (left, right) = (&caps[1], &caps[2]);
+            let hyphenated = text[..caps.get(1).unwrap().start()].ends_with(HYPHENS);
+            let compound = hyphenated
+                || right.starts_with(char::is_uppercase)
+                || HYPHENATED_PREFIXES.iter().any(|prefix| left.eq_ignore_ascii_case(prefix));
+            format!("{}{}{}", left, if compound { "-" } else { "" }, right)
+        })
+        .into_owned()
+}
+
+// Removes citation and footnote markers: numbers in brackets, superscript
+// numbers and, when `author_year` is set, parenthetical citations such as
+// "(Smith, 2020)". The space before a marker goes with it, so "as shown
+// [12]." becomes "as shown."
+pub fn strip_citations(text: &str, author_year: bool) -> String {
+    let text = REFERENCE_MARKER.replace_all(text, "");
+    if !author_year {
+        return text.into_owned();
+    }
+    AUTHOR_YEAR.replace_all(&text, "").into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_markdown_inline() {
+        assert_eq!(strip_markdown("Some **bold** and *italic* text."), "Some bold and italic text.");
+        assert_eq!(strip_markdown("Read [the docs](https://example.com/docs) now."), "Read the docs now.");
+        assert_eq!(strip_markdown("![A diagram](img.png) Call `run()` first."), "A diagram Call  first.");
+        assert_eq!(strip_markdown("## Getting started ##\nInstall it."), "Getting started\nInstall it.");
+        assert_eq!(strip_markdown("Keep snake_case words."), "Keep snake_case words.");
+    }
+
+    #[test]
+    fn test_strip_html() {
+        assert_eq!(strip_html("<p>Hello &amp; welcome</p>"), "Hello & welcome");
+        assert_eq!(strip_html("<p>One</p><p>Two&nbsp;words</p>"), "One Two words");
+        assert_eq!(strip_html("a &lt;b&gt; &#65;&#x42; &bogus;"), "a <b> AB &bogus;");
+        let page = "<html><head><style>p { color: red; }</style>\n<script>var x = 1;</script></head>\
+                    <body><!-- hidden --><h1>Title</h1>\n\n<p>Body text.</p></body></html>";
+        assert_eq!(strip_html(page), "Title Body text.");
+    }
+
+    #[test]
+    fn test_strip_markdown_blocks() {
+        let text = "Intro text.\n\n```rust\nlet x = HashMap::new();\n```\n\n[ref]: https://example.com\nOutro.";
+        assert_eq!(strip_markdown(text), "Intro text.\n\n\n\nOutro.");
+    }
+
+    #[test]
+    fn test_normalize_punctuation() {
+        assert_eq!(
+            normalize_punctuation("“Wait…” she said—then left. It’s 9–5."),
+            "\"Wait...\" she said--then left. It's 9--5."
+        );
+        assert_eq!(normalize_punctuation("plain 'ascii' text..."), "plain 'ascii' text...");
+    }
+
+    #[test]
+    fn test_strip_citations() {
+        assert_eq!(strip_citations("as shown [12].", false), "as shown.");
+        assert_eq!(strip_citations("Cats [3, 4] and dogs [5-7] sleep.² See [^a].", false), "Cats and dogs sleep. See.");
+        let cited = "Sleep helps (Smith, 2020). It also harms (Lee and Chen 2019; Park et al., 2021a, p. 4).";
+        assert_eq!(strip_citations(cited, true), "Sleep helps. It also harms.");
+        assert_eq!(strip_citations(cited, false), cited);
+        // Other brackets and parentheses stay
+        let plain = "Use [this] link (see below) in 2020 (or later).";
+        assert_eq!(strip_citations(plain, true), plain);
+    }
+
+    #[test]
+    fn test_rejoin_hyphenated_lines() {
+        let page = "The inter-\nnational commit-\r\n   tee met.\nIts self-\nesteem grew, and anti-\nAmerican views faded.";
+        assert_eq!(
+            rejoin_hyphenated_lines(page),
+            "The international committee met.\nIts self-esteem grew, and anti-American views faded."
+        );
+        // Hyphens that don't end a line, and dashes between words, stay
+        assert_eq!(rejoin_hyphenated_lines("A well-known fact -\nsadly."), "A well-known fact -\nsadly.");
+        // Compounds of several parts keep every hyphen
+        assert_eq!(rejoin_hyphenated_lines("A state-of-the-\nart lab."), "A state-of-the-art lab.");
+        assert_eq!(rejoin_hyphenated_lines("My mother-in-\nlaw came."), "My mother-in-law came.");
+    }
+
+    #[test]
+    fn test_strip_code() {
+        let text = "Call it like this:\n\n    let map = HashMap::new();\n\n    map.len()\nDone.\n";
+        assert_eq!(strip_code(text), "Call it like this:\n\nDone.\n");
+        let wrapped = "A paragraph that\n    continues indented.\n";
+        assert_eq!(strip_code(wrapped), wrapped);
+        assert_eq!(strip_code("Before.\n~~~\nfn main() {}\n~~~\nAfter."), "Before.\nAfter.");
+    }
+}
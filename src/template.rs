@@ -0,0 +1,128 @@
+use crate::TextMetrics;
+use std::fmt;
+
+// Why a template passed to `TextMetrics::render` can't be filled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    // A placeholder naming neither a CSV column nor a custom score
+    UnknownField(String),
+    // A format spec other than `.N`, e.g. the "x" in `{word_count:x}`
+    InvalidSpec(String),
+    // A `{` without its `}`, or a lone `}`; literal braces are doubled
+    UnmatchedBrace,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::UnknownField(name) => write!(f, "unknown field {{{}}}", name),
+            TemplateError::InvalidSpec(spec) => write!(f, "invalid format spec \"{}\"; only .N is supported", spec),
+            TemplateError::UnmatchedBrace => write!(f, "unmatched brace; write {{{{ or }}}} for a literal one"),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+impl TextMetrics {
+    // Fills a template such as "{word_count} words, ease {flesch_reading_ease:.1}"
+    // with the fields named by CSV_COLUMNS and the custom scores. Without a
+    // precision counts are integers and scores are printed in full; SMOG
+    // reads N/A when it is not valid. `{{` and `}}` are literal braces.
+    pub fn render(&self, template: &str) -> Result<String, TemplateError> {
+        let mut output = String::with_capacity(template.len());
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    output.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    output.push('}');
+                }
+                '}' => return Err(TemplateError::UnmatchedBrace),
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some('{') | None => return Err(TemplateError::UnmatchedBrace),
+                            Some(c) => placeholder.push(c),
+                        }
+                    }
+                    output.push_str(&self.placeholder_value(&placeholder)?);
+                }
+                c => output.push(c),
+            }
+        }
+        Ok(output)
+    }
+
+    fn placeholder_value(&self, placeholder: &str) -> Result<String, TemplateError> {
+        let (name, spec) = match placeholder.split_once(':') {
+            Some((name, spec)) => (name.trim(), Some(spec)),
+            None => (placeholder.trim(), None),
+        };
+        let precision = match spec {
+            Some(spec) => {
+                let digits = spec.strip_prefix('.').filter(|digits| !digits.is_empty());
+                match digits.and_then(|digits| digits.parse::<usize>().ok()) {
+                    Some(precision) => Some(precision),
+                    None => return Err(TemplateError::InvalidSpec(spec.to_string())),
+                }
+            }
+            None => None,
+        };
+        let (value, is_count) = match Self::CSV_COLUMNS.iter().position(|column| *column == name) {
+            Some(index) => (self.column_values()[index], index < self.counts().len()),
+            None => match self.custom.get(name) {
+                Some(&score) => (Some(score), false),
+                None => return Err(TemplateError::UnknownField(name.to_string())),
+            },
+        };
+        Ok(match (value, precision) {
+            (None, _) => "N/A".to_string(),
+            (Some(value), Some(precision)) => format!("{:.*}", precision, value),
+            (Some(value), None) if is_count => format!("{}", value as usize),
+            (Some(value), None) => format!("{}", value),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RawCounts, ReadabilityFormula, TextAnalyzer};
+
+    struct Half;
+
+    impl ReadabilityFormula for Half {
+        fn name(&self) -> &str {
+            "half"
+        }
+
+        fn score(&self, _: &RawCounts) -> f64 {
+            0.5
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let analyzer = TextAnalyzer::new().with_formula(Half);
+        let metrics = analyzer.analyze_text("The cat sat on the mat. It was happy.");
+        let rendered = metrics
+            .render("{word_count} words, {{ease}} {flesch_reading_ease:.1}, SMOG {smog_index:.2}, half {half}")
+            .unwrap();
+        let ease = format!("{:.1}", metrics.flesch_reading_ease);
+        assert_eq!(rendered, format!("9 words, {{ease}} {}, SMOG N/A, half 0.5", ease));
+        assert_eq!(metrics.render("{ sentence_count }").unwrap(), "2");
+        assert_eq!(metrics.render("{word_count:.2}").unwrap(), "9.00");
+
+        assert_eq!(metrics.render("{words}"), Err(TemplateError::UnknownField("words".to_string())));
+        assert_eq!(metrics.render("{word_count:>5}"), Err(TemplateError::InvalidSpec(">5".to_string())));
+        assert_eq!(metrics.render("{word_count"), Err(TemplateError::UnmatchedBrace));
+        assert_eq!(metrics.render("word_count}"), Err(TemplateError::UnmatchedBrace));
+    }
+}
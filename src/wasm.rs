@@ -0,0 +1,28 @@
+use crate::TextAnalyzer;
+use std::sync::LazyLock;
+use wasm_bindgen::prelude::*;
+
+static ANALYZER: LazyLock<TextAnalyzer> = LazyLock::new(TextAnalyzer::new);
+
+// Exported to JavaScript as `analyze_text(text: string): string`, returning
+// the TextMetrics as JSON. After `wasm-pack build --target web -- --features wasm`:
+//
+//     import init, { analyze_text } from "./pkg/fog.js";
+//     await init();
+//     const metrics = JSON.parse(analyze_text("The cat sat on the mat."));
+//     console.log(metrics.flesch_reading_ease);
+#[wasm_bindgen]
+pub fn analyze_text(text: &str) -> String {
+    ANALYZER.analyze_text(text).to_json()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_text_json() {
+        let expected = TextAnalyzer::new().analyze_text("The cat sat on the mat.");
+        assert_eq!(analyze_text("The cat sat on the mat."), expected.to_json());
+    }
+}